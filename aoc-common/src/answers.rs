@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, ErrorKind, Read};
+use std::path::Path;
+use std::process::ExitCode;
+
+use crate::{read_input, solve, Error, Result, Solution};
+
+/// Answer file used by the day binaries, relative to the day's crate.
+pub const DEFAULT_ANSWERS: &str = "../answers.txt";
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// 64-bit FNV-1a hash of a puzzle input, used to tell inputs apart without
+/// storing them.
+pub fn input_hash(input: &str) -> u64 {
+    fnv(FNV_OFFSET, input.as_bytes())
+}
+
+fn fnv(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Reader that hashes everything read through it, so a streamed input can be
+/// checked against the answer file like one read into memory.
+pub struct HashingReader<R> {
+    inner: R,
+    hash: u64,
+}
+
+impl<R: BufRead> HashingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hash: FNV_OFFSET,
+        }
+    }
+
+    /// [`input_hash`] of everything read so far.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl<R: BufRead> Read for HashingReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let buffer = self.inner.fill_buf()?;
+        let length = buffer.len().min(out.len());
+        out[..length].copy_from_slice(&buffer[..length]);
+        self.hash = fnv(self.hash, &buffer[..length]);
+        self.inner.consume(length);
+        Ok(length)
+    }
+}
+
+impl<R: BufRead> BufRead for HashingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        // the buffer is already filled, so this only looks at it again
+        if let Ok(buffer) = self.inner.fill_buf() {
+            self.hash = fnv(self.hash, &buffer[..amount]);
+        }
+        self.inner.consume(amount);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail(String),
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "pass"),
+            Self::Fail(expected) => write!(f, "FAIL, expected {expected}"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Expected answers keyed by input hash, day and part.
+///
+/// The file has one `HASH DAY PART ANSWER` entry per line, with the hash in
+/// hex and newlines in the answer written as `\n`. Empty lines and lines
+/// starting with `#` are skipped.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    entries: BTreeMap<(u64, u8, u8), String>,
+}
+
+impl Answers {
+    pub fn parse(path: &str, contents: &str) -> Result<Self> {
+        let mut answers = Self::default();
+
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let malformed = || Error::Format(path.to_string(), i + 1, "HASH DAY PART ANSWER");
+
+            let mut fields = line.splitn(4, ' ');
+            let mut next = || fields.next().ok_or_else(malformed);
+            let hash = u64::from_str_radix(next()?, 16).map_err(|_| malformed())?;
+            let day = next()?.parse().map_err(|_| malformed())?;
+            let part = next()?.parse().map_err(|_| malformed())?;
+            let answer = unescape(next()?);
+
+            answers.entries.insert((hash, day, part), answer);
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, hash: u64, day: u8, part: u8) -> Option<&str> {
+        self.entries.get(&(hash, day, part)).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, hash: u64, day: u8, part: u8, answer: &str) {
+        self.entries.insert((hash, day, part), answer.to_string());
+    }
+
+    pub fn check(&self, hash: u64, day: u8, part: u8, answer: &str) -> Verdict {
+        match self.get(hash, day, part) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected.to_string()),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((hash, day, part), answer) in &self.entries {
+            writeln!(f, "{hash:016x} {day} {part} {}", escape(answer))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads an answer file, a missing file is an empty registry.
+pub fn read_answers<P: AsRef<Path>>(path: P) -> Result<Answers> {
+    let path = path.as_ref();
+    let display = path.display().to_string();
+
+    match fs::read_to_string(path) {
+        Ok(contents) => Answers::parse(&display, &contents),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+        Err(error) => Err(Error::Read(display, error)),
+    }
+}
+
+pub fn write_answers<P: AsRef<Path>>(path: P, answers: &Answers) -> Result<()> {
+    let path = path.as_ref();
+
+    fs::write(path, answers.to_string())
+        .map_err(|error| Error::Write(path.display().to_string(), error))
+}
+
+/// Entry point of the day binaries: solves `input/input.txt` and checks both
+/// answers against [`DEFAULT_ANSWERS`].
+pub fn run_day<S: Solution>() -> ExitCode {
+    let checked = read_input().and_then(|input| {
+        let answers = read_answers(DEFAULT_ANSWERS)?;
+        let hash = input_hash(&input);
+
+        let mut status = ExitCode::SUCCESS;
+        for part in 1..=2 {
+            let answer = solve::<S>(&input, part)?;
+            let verdict = answers.check(hash, S::DAY, part, &answer);
+            println!("Part {part}: {answer} ({verdict})");
+            if let Verdict::Fail(_) = verdict {
+                status = ExitCode::FAILURE;
+            }
+        }
+        Ok(status)
+    });
+
+    checked.unwrap_or_else(|error| {
+        eprintln!("{error}");
+        ExitCode::FAILURE
+    })
+}
+
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut unescaped = String::with_capacity(answer.len());
+    let mut chars = answer.chars();
+    while let Some(ch) = chars.next() {
+        match (ch, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                unescaped.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                unescaped.push('\\');
+                chars.next();
+            }
+            _ => unescaped.push(ch),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_hash() {
+        assert_eq!(input_hash(""), 0xcbf29ce484222325);
+        assert_eq!(input_hash("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_hashing_reader() {
+        let input = "498,4 -> 498,6\n503,4\n";
+        let mut reader = HashingReader::new(input.as_bytes());
+        let mut first = String::new();
+        reader.read_line(&mut first).unwrap();
+        let rest = crate::read_from("<test>", &mut reader).unwrap();
+        assert_eq!(first + &rest, input);
+        assert_eq!(reader.hash(), input_hash(input));
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse(
+            "answers.txt",
+            "# comment\n00000000000000ff 5 1 CMZ\n00000000000000ff 10 2 \\n#.\\\\\n",
+        )
+        .unwrap();
+        assert_eq!(answers.check(0xff, 5, 1, "CMZ"), Verdict::Pass);
+        assert_eq!(
+            answers.check(0xff, 5, 1, "MCD"),
+            Verdict::Fail("CMZ".into())
+        );
+        assert_eq!(answers.check(0xff, 5, 2, "MCD"), Verdict::Unknown);
+        assert_eq!(answers.get(0xff, 10, 2), Some("\n#.\\"));
+        assert_eq!(
+            Answers::parse("answers.txt", &answers.to_string()).unwrap(),
+            answers
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        let error = Answers::parse("answers.txt", "ff 1 1 42\nff one 2 42\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "File answers.txt line 2 is not `HASH DAY PART ANSWER`"
+        );
+    }
+}
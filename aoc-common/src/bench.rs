@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::{read_input_from, Error, ParseError, Result, Solution};
+
+pub const PHASES: [&str; 3] = ["parse", "part1", "part2"];
+
+/// Spread of the timed runs of a single phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Panics when `samples` is empty.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Timings of one day, in the order of [`PHASES`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timings {
+    pub day: u8,
+    pub phases: [Stats; 3],
+}
+
+/// Times parsing and both parts of `S` separately, `runs` times each.
+pub fn bench<S: Solution>(input: &str, runs: usize) -> std::result::Result<Timings, ParseError> {
+    let parsed = S::parse(input)?;
+
+    Ok(Timings {
+        day: S::DAY,
+        phases: [
+            time_runs(runs, || S::parse(input)),
+            time_runs(runs, || S::part1(&parsed)),
+            time_runs(runs, || S::part2(&parsed)),
+        ],
+    })
+}
+
+fn time_runs<T>(runs: usize, mut phase: impl FnMut() -> T) -> Stats {
+    let samples = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(phase());
+            start.elapsed()
+        })
+        .collect();
+
+    Stats::from_samples(samples)
+}
+
+/// Saved medians to compare a benchmark run against.
+///
+/// The file has one `DAY PHASE NANOSECONDS` entry per line.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: BTreeMap<(u8, String), Duration>,
+}
+
+impl Baseline {
+    pub fn parse(path: &str, contents: &str) -> Result<Self> {
+        let mut baseline = Self::default();
+
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let malformed = || Error::Format(path.to_string(), i + 1, "DAY PHASE NANOSECONDS");
+
+            let [day, phase, nanos] = line.split(' ').collect::<Vec<&str>>()[..] else {
+                return Err(malformed());
+            };
+            let day = day.parse().map_err(|_| malformed())?;
+            let nanos = nanos.parse().map_err(|_| malformed())?;
+
+            baseline
+                .medians
+                .insert((day, phase.to_string()), Duration::from_nanos(nanos));
+        }
+
+        Ok(baseline)
+    }
+
+    pub fn get(&self, day: u8, phase: &str) -> Option<Duration> {
+        self.medians.get(&(day, phase.to_string())).copied()
+    }
+
+    pub fn insert(&mut self, timings: &Timings) {
+        for (phase, stats) in PHASES.iter().zip(timings.phases) {
+            self.medians
+                .insert((timings.day, phase.to_string()), stats.median);
+        }
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, phase), median) in &self.medians {
+            writeln!(f, "{day} {phase} {}", median.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+pub fn read_baseline<P: AsRef<Path>>(path: P) -> Result<Baseline> {
+    let contents = read_input_from(&path)?;
+
+    Baseline::parse(&path.as_ref().display().to_string(), &contents)
+}
+
+pub fn write_baseline<P: AsRef<Path>>(path: P, baseline: &Baseline) -> Result<()> {
+    let path = path.as_ref();
+
+    fs::write(path, baseline.to_string())
+        .map_err(|error| Error::Write(path.display().to_string(), error))
+}
+
+/// Relative change from `baseline` to `median` in percent.
+pub fn change(baseline: Duration, median: Duration) -> f64 {
+    (median.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(ms(&[5, 1, 9]));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(5));
+        assert_eq!(stats.max, Duration::from_millis(9));
+        assert_eq!(
+            Stats::from_samples(ms(&[4, 1, 2, 9])).median,
+            Duration::from_millis(3)
+        );
+    }
+
+    #[test]
+    fn test_baseline() {
+        let mut baseline = Baseline::default();
+        let stats = Stats::from_samples(ms(&[2]));
+        baseline.insert(&Timings {
+            day: 7,
+            phases: [stats; 3],
+        });
+        assert_eq!(baseline.get(7, "part2"), Some(Duration::from_millis(2)));
+        assert_eq!(baseline.get(8, "part2"), None);
+        assert_eq!(
+            Baseline::parse("baseline.txt", &baseline.to_string()).unwrap(),
+            baseline
+        );
+        assert!(Baseline::parse("baseline.txt", "7 parse\n").is_err());
+    }
+
+    #[test]
+    fn test_change() {
+        let change = change(Duration::from_millis(200), Duration::from_millis(150));
+        assert!((change + 25.0).abs() < 1e-9);
+    }
+}
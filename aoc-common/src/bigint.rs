@@ -0,0 +1,259 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// Unsigned integer of any size, for puzzle modes whose numbers outgrow
+/// `u64`. It has only the arithmetic the solvers need.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Base 2^32 digits, least significant first, without leading zeros.
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low as u64),
+            [low, high] => Some((high as u64) << 32 | low as u64),
+            _ => None,
+        }
+    }
+
+    /// Number of bits, 0 for zero.
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(high) => self.limbs.len() as u64 * 32 - high.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    fn bit(&self, index: u64) -> bool {
+        let limb = self.limbs.get((index / 32) as usize).unwrap_or(&0);
+        limb >> (index % 32) & 1 == 1
+    }
+
+    fn normalized(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    /// Quotient and remainder of division by `divisor`, which must not be 0.
+    fn div_rem(&self, divisor: u64) -> (BigUint, u64) {
+        assert!(divisor != 0, "attempt to divide by zero");
+
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = 0u128;
+        for (i, limb) in self.limbs.iter().enumerate().rev() {
+            let current = remainder << 32 | *limb as u128;
+            // below 2^32 because `remainder` is below `divisor`
+            quotient[i] = (current / divisor as u128) as u32;
+            remainder = current % divisor as u128;
+        }
+
+        let quotient = BigUint { limbs: quotient }.normalized();
+        (quotient, remainder as u64)
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint {
+            limbs: vec![value as u32, (value >> 32) as u32],
+        }
+        .normalized()
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut limbs = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        let mut carry = 0u64;
+        for i in 0..self.limbs.len().max(other.limbs.len()) {
+            let sum = carry
+                + *self.limbs.get(i).unwrap_or(&0) as u64
+                + *other.limbs.get(i).unwrap_or(&0) as u64;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        limbs.push(carry as u32);
+
+        BigUint { limbs }.normalized()
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut limbs = vec![0u32; self.limbs.len() + other.limbs.len()];
+        for (i, a) in self.limbs.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, b) in other.limbs.iter().enumerate() {
+                // at most (2^32 - 1)^2 + 2 * (2^32 - 1), which fits
+                let product = *a as u64 * *b as u64 + limbs[i + j] as u64 + carry;
+                limbs[i + j] = product as u32;
+                carry = product >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        BigUint { limbs }.normalized()
+    }
+}
+
+impl Sub for &BigUint {
+    type Output = BigUint;
+
+    /// Panics when `other` is larger, like `u64` subtraction with overflow
+    /// checks on.
+    fn sub(self, other: &BigUint) -> BigUint {
+        assert!(self >= other, "attempt to subtract with overflow");
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0i64;
+        for (i, limb) in self.limbs.iter().enumerate() {
+            let difference = *limb as i64 - *other.limbs.get(i).unwrap_or(&0) as i64 - borrow;
+            limbs.push(difference.rem_euclid(1 << 32) as u32);
+            borrow = (difference < 0) as i64;
+        }
+
+        BigUint { limbs }.normalized()
+    }
+}
+
+impl Div<u64> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, divisor: u64) -> BigUint {
+        self.div_rem(divisor).0
+    }
+}
+
+impl Rem<u64> for &BigUint {
+    type Output = u64;
+
+    fn rem(self, divisor: u64) -> u64 {
+        self.div_rem(divisor).1
+    }
+}
+
+/// Remainder by a divisor of any size, worked out one bit at a time. A
+/// divisor that fits in a `u64` takes the faster way.
+impl Rem for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, divisor: &BigUint) -> BigUint {
+        if let Some(divisor) = divisor.to_u64() {
+            return BigUint::from(self % divisor);
+        }
+        if self < divisor {
+            return self.clone();
+        }
+
+        let mut remainder = BigUint::default();
+        for index in (0..self.bits()).rev() {
+            remainder = &remainder + &remainder;
+            if self.bit(index) {
+                remainder = &remainder + &BigUint::from(1);
+            }
+            if remainder >= *divisor {
+                remainder = &remainder - divisor;
+            }
+        }
+
+        remainder
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u64 = 1_000_000_000;
+
+        // nine decimal digits at a time, least significant first
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, chunk) = rest.div_rem(CHUNK);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+
+        match chunks.split_last() {
+            None => f.pad_integral(true, "", "0"),
+            Some((first, others)) => {
+                let mut digits = first.to_string();
+                for chunk in others.iter().rev() {
+                    digits += &format!("{chunk:09}");
+                }
+                f.pad_integral(true, "", &digits)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small() {
+        let (a, b) = (BigUint::from(123456789), BigUint::from(987654321));
+        assert_eq!((&a + &b).to_u64(), Some(1111111110));
+        assert_eq!((&a * &b).to_u64(), Some(121932631112635269));
+        assert_eq!((&b / 1000).to_u64(), Some(987654));
+        assert_eq!(&b % 1000, 321);
+        assert!(a < b);
+        assert!(BigUint::from(0).is_zero());
+        assert_eq!(BigUint::from(0).to_string(), "0");
+    }
+
+    #[test]
+    fn test_large() {
+        // 2^128 = 340282366920938463463374607431768211456
+        let two64 = &BigUint::from(u64::MAX) + &BigUint::from(1);
+        let two128 = &two64 * &two64;
+        assert_eq!(two128.to_u64(), None);
+        assert_eq!(two128.bits(), 129);
+        assert_eq!(
+            two128.to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(&two128 % 1_000_000_007, 279_632_277);
+        assert_eq!(&two128 % 7, 4);
+        let multiple = &(&two128 / 7) * &BigUint::from(7);
+        assert_eq!(&multiple + &BigUint::from(4), two128);
+        assert!(two64 < two128);
+        assert_eq!(&two128 - &two128, BigUint::default());
+        assert_eq!(&(&two128 - &BigUint::from(1)) + &BigUint::from(1), two128);
+        assert_eq!(&two128 % &two64, BigUint::default());
+        let three = BigUint::from(3);
+        let odd = &(&two128 * &three) + &(&two64 + &BigUint::from(5));
+        assert_eq!(&odd % &(&two64 * &three), &two64 + &BigUint::from(5));
+        assert_eq!(&odd % &BigUint::from(7), BigUint::from(&odd % 7));
+        assert_eq!(format!("{:>6}", BigUint::from(42)), "    42");
+    }
+}
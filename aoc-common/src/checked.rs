@@ -0,0 +1,95 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+use crate::{Error, Result};
+
+thread_local! {
+    /// Where the last overflow on this thread happened.
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Runs `solve`, turning an arithmetic overflow in it into
+/// [`Error::Overflow`] for `day` and `part`, `None` when both parts were
+/// being solved. Other panics carry on unwinding.
+///
+/// Overflows only panic with overflow checks on, as in debug builds and the
+/// workspace's `checked` profile; release builds wrap around silently.
+pub fn catch_overflow<T>(
+    day: u8,
+    part: Option<u8>,
+    solve: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    static QUIET: Once = Once::new();
+    // report overflows through the error alone, other panics as usual
+    QUIET.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| match overflow(info.payload()) {
+            Some(_) => LOCATION.set(info.location().map(ToString::to_string)),
+            None => previous(info),
+        }));
+    });
+
+    match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(solved) => solved,
+        Err(payload) => {
+            let Some(message) = overflow(payload.as_ref()) else {
+                panic::resume_unwind(payload);
+            };
+            let operation = match LOCATION.take() {
+                Some(location) => format!("{message} at {location}"),
+                None => message.to_string(),
+            };
+            Err(Error::Overflow {
+                day,
+                part,
+                operation,
+            })
+        }
+    }
+}
+
+/// The message of a panic raised by an overflow check, like `attempt to add
+/// with overflow`.
+fn overflow(payload: &(dyn Any + Send)) -> Option<&str> {
+    let message = match payload.downcast_ref::<&str>() {
+        Some(message) => message,
+        None => payload.downcast_ref::<String>()?.as_str(),
+    };
+
+    (message.starts_with("attempt to") && message.ends_with("with overflow")).then_some(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_overflow() {
+        assert_eq!(catch_overflow(1, Some(1), || Ok(2 + 2)).unwrap(), 4);
+        assert!(matches!(
+            catch_overflow(1, Some(1), || Err::<i32, _>(Error::NoImage(1))),
+            Err(Error::NoImage(1))
+        ));
+    }
+
+    // overflow checks are on in debug builds only
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_overflow() {
+        let big = std::hint::black_box(i32::MAX);
+        let Err(error) = catch_overflow(7, Some(2), || Ok(big + 1)) else {
+            panic!("expected an overflow");
+        };
+        assert!(error
+            .to_string()
+            .starts_with("Day 7 part 2 overflowed: attempt to add with overflow at "));
+    }
+
+    #[test]
+    #[should_panic(expected = "not an overflow")]
+    fn test_other_panic() {
+        let _ = catch_overflow(1, None, || -> Result<()> { panic!("not an overflow") });
+    }
+}
@@ -0,0 +1,459 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::Simulation;
+
+pub const DEBUG_HELP: &str = "Commands:
+    step [N], s [N]     Advance N steps, defaults to 1
+    run, r              Advance until a breakpoint or the end
+    until COND, u COND  Advance until COND holds
+    break COND, b COND  Stop whenever COND starts to hold
+    delete N, d N       Remove breakpoint N
+    breaks              List the breakpoints
+    print [NAME], p     Show the state, or the value of one variable
+    help, h             Show this help
+    quit, q             Leave the debugger
+An empty line repeats the last command. COND compares a variable with a
+number, like `tail_x >= 3`, using ==, !=, <, <=, > or >=.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    const ALL: [(&'static str, Comparison); 7] = [
+        ("==", Self::Equal),
+        ("=", Self::Equal),
+        ("!=", Self::NotEqual),
+        ("<=", Self::LessOrEqual),
+        ("<", Self::Less),
+        (">=", Self::GreaterOrEqual),
+        (">", Self::Greater),
+    ];
+
+    fn holds(self, left: i64, right: i64) -> bool {
+        match self {
+            Self::Equal => left == right,
+            Self::NotEqual => left != right,
+            Self::Less => left < right,
+            Self::LessOrEqual => left <= right,
+            Self::Greater => left > right,
+            Self::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+/// A variable compared with a number, like `tail_x >= 3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    pub name: String,
+    pub comparison: Comparison,
+    pub value: i64,
+}
+
+impl Condition {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let invalid = || format!("`{text}` is not a condition like `tail_x >= 3`");
+
+        let at = text.find(['=', '!', '<', '>']).ok_or_else(invalid)?;
+        let (name, rest) = (text[..at].trim(), &text[at..]);
+        let (op, comparison) = Comparison::ALL
+            .into_iter()
+            .find(|(op, _)| rest.starts_with(op))
+            .ok_or_else(invalid)?;
+        let value = rest[op.len()..].trim().parse().map_err(|_| invalid())?;
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            comparison,
+            value,
+        })
+    }
+
+    /// Whether the condition holds for `variables`, `None` when it names none
+    /// of them.
+    fn holds(&self, variables: &[(&str, i64)]) -> Option<bool> {
+        let (_, value) = variables.iter().find(|(name, _)| *name == self.name)?;
+        Some(self.comparison.holds(*value, self.value))
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (op, _) = Comparison::ALL
+            .into_iter()
+            .find(|(_, comparison)| *comparison == self.comparison)
+            .unwrap();
+        write!(f, "{} {op} {}", self.name, self.value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DebugCommand {
+    Step(u64),
+    Run,
+    Until(Condition),
+    Break(Condition),
+    Delete(usize),
+    Breaks,
+    Print(Option<String>),
+    Help,
+    Quit,
+}
+
+impl DebugCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim();
+        let number = |what: &str| {
+            rest.parse::<u64>()
+                .map_err(|_| format!("`{word}` takes {what}, found `{rest}`"))
+        };
+
+        match word {
+            "step" | "s" if rest.is_empty() => Ok(Self::Step(1)),
+            "step" | "s" => Ok(Self::Step(number("a number of steps")?)),
+            "run" | "r" if rest.is_empty() => Ok(Self::Run),
+            "until" | "u" => Ok(Self::Until(Condition::parse(rest)?)),
+            "break" | "b" => Ok(Self::Break(Condition::parse(rest)?)),
+            "delete" | "d" => Ok(Self::Delete(number("a breakpoint number")? as usize)),
+            "breaks" if rest.is_empty() => Ok(Self::Breaks),
+            "print" | "p" if rest.is_empty() => Ok(Self::Print(None)),
+            "print" | "p" => Ok(Self::Print(Some(rest.to_string()))),
+            "help" | "h" => Ok(Self::Help),
+            "quit" | "q" => Ok(Self::Quit),
+            _ => Err(format!("Unknown command `{line}`, `help` lists them")),
+        }
+    }
+}
+
+/// Steps a simulation under the control of [`DebugCommand`]s, keeping count
+/// of the steps taken as the `step` variable.
+pub struct Debugger<'a> {
+    simulation: &'a mut dyn Simulation,
+    steps: u64,
+    finished: bool,
+    breakpoints: Vec<Condition>,
+}
+
+impl<'a> Debugger<'a> {
+    pub fn new(simulation: &'a mut dyn Simulation) -> Self {
+        Self {
+            simulation,
+            steps: 0,
+            finished: false,
+            breakpoints: Vec::new(),
+        }
+    }
+
+    /// The simulation's variables after `step`.
+    pub fn variables(&self) -> Vec<(&'static str, i64)> {
+        let mut variables = vec![("step", self.steps as i64)];
+        variables.extend(self.simulation.variables());
+        variables
+    }
+
+    /// Carries out `command` and returns what to show, or an error for
+    /// commands that name no variable or breakpoint. `Quit` does nothing.
+    pub fn execute(&mut self, command: &DebugCommand) -> Result<String, String> {
+        match command {
+            DebugCommand::Step(count) => Ok(self.advance(Some(*count), None)),
+            DebugCommand::Run => Ok(self.advance(None, None)),
+            DebugCommand::Until(condition) => {
+                self.check(condition)?;
+                Ok(self.advance(None, Some(condition)))
+            }
+            DebugCommand::Break(condition) => {
+                self.check(condition)?;
+                self.breakpoints.push(condition.clone());
+                Ok(format!(
+                    "Breakpoint {}: {condition}",
+                    self.breakpoints.len()
+                ))
+            }
+            DebugCommand::Delete(number) => {
+                if !(1..=self.breakpoints.len()).contains(number) {
+                    return Err(format!("There is no breakpoint {number}"));
+                }
+                let condition = self.breakpoints.remove(number - 1);
+                Ok(format!("Deleted breakpoint {number}: {condition}"))
+            }
+            DebugCommand::Breaks => Ok(self.breaks()),
+            DebugCommand::Print(None) => {
+                Ok(format!("{}{}", self.simulation.frame(), self.summary()))
+            }
+            DebugCommand::Print(Some(name)) => {
+                let variables = self.variables();
+                match variables.iter().find(|(variable, _)| variable == name) {
+                    Some((_, value)) => Ok(format!("{name} = {value}")),
+                    None => Err(self.unknown(name)),
+                }
+            }
+            DebugCommand::Help => Ok(DEBUG_HELP.to_string()),
+            DebugCommand::Quit => Ok(String::new()),
+        }
+    }
+
+    /// Steps until `limit` steps were taken, `until` holds, a breakpoint
+    /// starts to hold or the simulation finishes.
+    fn advance(&mut self, limit: Option<u64>, until: Option<&Condition>) -> String {
+        if self.finished {
+            return format!("Finished after {} steps", self.steps);
+        }
+
+        let mut taken = 0;
+        let mut held: Vec<bool> = self.breakpoints_holding();
+        loop {
+            if limit == Some(taken) {
+                return self.summary();
+            }
+            if !self.simulation.step() {
+                self.finished = true;
+                return format!("Finished after {} steps\n{}", self.steps, self.summary());
+            }
+            self.steps += 1;
+            taken += 1;
+            if until.is_none() && self.breakpoints.is_empty() {
+                continue;
+            }
+
+            let variables = self.variables();
+            if until.is_some_and(|condition| condition.holds(&variables) == Some(true)) {
+                return self.summary();
+            }
+            let holding = self.breakpoints_holding();
+            let hit = (0..holding.len()).find(|i| holding[*i] && !held[*i]);
+            if let Some(i) = hit {
+                return format!(
+                    "Breakpoint {}: {}\n{}",
+                    i + 1,
+                    self.breakpoints[i],
+                    self.summary()
+                );
+            }
+            held = holding;
+        }
+    }
+
+    fn breakpoints_holding(&self) -> Vec<bool> {
+        let variables = self.variables();
+        self.breakpoints
+            .iter()
+            .map(|condition| condition.holds(&variables) == Some(true))
+            .collect()
+    }
+
+    fn breaks(&self) -> String {
+        if self.breakpoints.is_empty() {
+            return String::from("No breakpoints");
+        }
+        let lines: Vec<String> = (1..)
+            .zip(&self.breakpoints)
+            .map(|(number, condition)| format!("{number}: {condition}"))
+            .collect();
+        lines.join("\n")
+    }
+
+    /// All variables on one line.
+    fn summary(&self) -> String {
+        let values: Vec<String> = self
+            .variables()
+            .iter()
+            .map(|(name, value)| format!("{name} = {value}"))
+            .collect();
+        values.join(", ")
+    }
+
+    fn check(&self, condition: &Condition) -> Result<(), String> {
+        match condition.holds(&self.variables()) {
+            Some(_) => Ok(()),
+            None => Err(self.unknown(&condition.name)),
+        }
+    }
+
+    fn unknown(&self, name: &str) -> String {
+        let names: Vec<&str> = self.variables().iter().map(|(name, _)| *name).collect();
+        format!(
+            "Unknown variable `{name}`, expected one of {}",
+            names.join(", ")
+        )
+    }
+}
+
+/// Runs the debugger on `simulation`, reading commands from `input` until it
+/// ends or `quit`, and writing prompts and results to `output`.
+pub fn debug(
+    simulation: &mut dyn Simulation,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut debugger = Debugger::new(simulation);
+    writeln!(output, "{}", debugger.summary())?;
+
+    let mut last: Option<DebugCommand> = None;
+    loop {
+        write!(output, "(debug) ")?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return writeln!(output);
+        }
+
+        let command = match (line.trim(), &last) {
+            ("", Some(last)) => Ok(last.clone()),
+            ("", None) => continue,
+            (line, _) => DebugCommand::parse(line),
+        };
+        let command = match command {
+            Ok(DebugCommand::Quit) => return Ok(()),
+            Ok(command) => command,
+            Err(error) => {
+                writeln!(output, "{error}")?;
+                continue;
+            }
+        };
+        match debugger.execute(&command) {
+            Ok(shown) => writeln!(output, "{shown}")?,
+            Err(error) => writeln!(output, "{error}")?,
+        }
+        last = Some(command);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts down from its start, one per step.
+    struct Countdown(i64);
+
+    impl Simulation for Countdown {
+        fn step(&mut self) -> bool {
+            if self.0 == 0 {
+                return false;
+            }
+            self.0 -= 1;
+            true
+        }
+
+        fn frame(&self) -> String {
+            format!("{}\n", self.0)
+        }
+
+        fn variables(&self) -> Vec<(&'static str, i64)> {
+            vec![("left", self.0)]
+        }
+    }
+
+    fn execute(debugger: &mut Debugger, line: &str) -> Result<String, String> {
+        debugger.execute(&DebugCommand::parse(line)?)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(DebugCommand::parse("s"), Ok(DebugCommand::Step(1)));
+        assert_eq!(DebugCommand::parse("step 20"), Ok(DebugCommand::Step(20)));
+        assert_eq!(
+            DebugCommand::parse("b tail_x>=-3"),
+            Ok(DebugCommand::Break(Condition {
+                name: String::from("tail_x"),
+                comparison: Comparison::GreaterOrEqual,
+                value: -3,
+            }))
+        );
+        assert_eq!(
+            Condition::parse(" x  !=  2 ").unwrap().to_string(),
+            "x != 2"
+        );
+        assert!(DebugCommand::parse("step x").is_err());
+        assert!(DebugCommand::parse("until x").is_err());
+        assert!(DebugCommand::parse("until tail x > 1").is_err());
+        assert!(DebugCommand::parse("jump").is_err());
+    }
+
+    #[test]
+    fn test_step_and_until() {
+        let mut countdown = Countdown(10);
+        let mut debugger = Debugger::new(&mut countdown);
+        assert_eq!(
+            execute(&mut debugger, "step 3"),
+            Ok(String::from("step = 3, left = 7"))
+        );
+        assert_eq!(
+            execute(&mut debugger, "until left <= 2"),
+            Ok(String::from("step = 8, left = 2"))
+        );
+        assert_eq!(
+            execute(&mut debugger, "p left"),
+            Ok(String::from("left = 2"))
+        );
+        assert_eq!(
+            execute(&mut debugger, "print"),
+            Ok(String::from("2\nstep = 8, left = 2"))
+        );
+        assert_eq!(
+            execute(&mut debugger, "run"),
+            Ok(String::from("Finished after 10 steps\nstep = 10, left = 0"))
+        );
+        assert_eq!(
+            execute(&mut debugger, "s"),
+            Ok(String::from("Finished after 10 steps"))
+        );
+        assert!(execute(&mut debugger, "until right > 1").is_err());
+        assert!(execute(&mut debugger, "p right").is_err());
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let mut countdown = Countdown(10);
+        let mut debugger = Debugger::new(&mut countdown);
+        assert_eq!(
+            execute(&mut debugger, "break left < 5"),
+            Ok(String::from("Breakpoint 1: left < 5"))
+        );
+        execute(&mut debugger, "b step = 2").unwrap();
+        assert_eq!(
+            execute(&mut debugger, "run"),
+            Ok(String::from("Breakpoint 2: step == 2\nstep = 2, left = 8"))
+        );
+        assert_eq!(
+            execute(&mut debugger, "run"),
+            Ok(String::from("Breakpoint 1: left < 5\nstep = 6, left = 4"))
+        );
+        // a breakpoint only stops again once it stopped holding
+        assert!(execute(&mut debugger, "run")
+            .unwrap()
+            .starts_with("Finished"));
+        assert_eq!(
+            execute(&mut debugger, "breaks"),
+            Ok(String::from("1: left < 5\n2: step == 2"))
+        );
+        execute(&mut debugger, "delete 1").unwrap();
+        assert!(execute(&mut debugger, "delete 2").is_err());
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut output = Vec::new();
+        let commands = "step 2\n\nbogus\nquit\nstep\n";
+        debug(&mut Countdown(5), commands.as_bytes(), &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "step = 0, left = 5\n\
+             (debug) step = 2, left = 3\n\
+             (debug) step = 4, left = 1\n\
+             (debug) Unknown command `bogus`, `help` lists them\n\
+             (debug) "
+        );
+    }
+}
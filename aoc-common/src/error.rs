@@ -0,0 +1,173 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    Open(String, std::io::Error),
+    Read(String, std::io::Error),
+    Write(String, std::io::Error),
+    Format(String, usize, &'static str),
+    Parse(ParseError),
+    NoSolver {
+        day: u8,
+        part: u8,
+    },
+    NoAnswer {
+        day: u8,
+        part: u8,
+    },
+    NoSimulation(u8),
+    NoImage(u8),
+    NoStream(u8),
+    NoGenerator(u8),
+    Overflow {
+        day: u8,
+        part: Option<u8>,
+        operation: String,
+    },
+    Exists(String),
+    NoAnchor(String, &'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open(path, error) => write!(f, "File {path} failed to open: {error}"),
+            Self::Read(path, error) => {
+                write!(f, "File {path} failed to be read to String: {error}")
+            }
+            Self::Write(path, error) => write!(f, "File {path} failed to be written: {error}"),
+            Self::Format(path, line, format) => {
+                write!(f, "File {path} line {line} is not `{format}`")
+            }
+            Self::Parse(error) => write!(f, "{error}"),
+            Self::NoSolver { day, part } => write!(f, "Day {day} part {part} has no solver"),
+            Self::NoAnswer { day, part } => write!(f, "Day {day} part {part} has no answer"),
+            Self::NoSimulation(day) => write!(f, "Day {day} has no simulation to animate"),
+            Self::NoImage(day) => write!(f, "Day {day} has no grid to draw"),
+            Self::NoStream(day) => write!(f, "Day {day} has no streaming solver"),
+            Self::NoGenerator(day) => write!(f, "Day {day} has no input generator"),
+            Self::Overflow {
+                day,
+                part: Some(part),
+                operation,
+            } => write!(f, "Day {day} part {part} overflowed: {operation}"),
+            Self::Overflow {
+                day,
+                part: None,
+                operation,
+            } => write!(f, "Day {day} overflowed: {operation}"),
+            Self::Exists(path) => write!(f, "File {path} already exists"),
+            Self::NoAnchor(path, anchor) => {
+                write!(f, "File {path} has no {anchor} to add the new day after")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Open(_, error) | Self::Read(_, error) | Self::Write(_, error) => Some(error),
+            Self::Parse(error) => Some(error),
+            Self::Format(..)
+            | Self::NoSolver { .. }
+            | Self::NoAnswer { .. }
+            | Self::NoSimulation(_)
+            | Self::NoImage(_)
+            | Self::NoStream(_)
+            | Self::NoGenerator(_)
+            | Self::Overflow { .. }
+            | Self::Exists(_)
+            | Self::NoAnchor(..) => None,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+/// Malformed puzzle input. `line` and `column` are 1-based, `text` is the
+/// offending part of the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, line: usize, column: usize, text: &str, expected: &str) -> Self {
+        Self {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    /// Error pointing at `text`, which must be a slice of `line`; `index` is the
+    /// 0-based line index as given by `lines().enumerate()`.
+    pub fn at(day: u8, index: usize, line: &str, text: &str, expected: &str) -> Self {
+        Self::new(day, index + 1, column_of(line, text), text, expected)
+    }
+
+    /// Error for input that ended while `expected` was still missing.
+    pub fn eof(day: u8, input: &str, expected: &str) -> Self {
+        Self::new(day, input.lines().count() + 1, 1, "", expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {} input, line {}, column {}: expected {}, found `{}`",
+            self.day, self.line, self.column, self.expected, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// 1-based character column of `text` inside `line`, or 1 when `text` is not
+/// a slice of `line`.
+fn column_of(line: &str, text: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let offset = (text.as_ptr() as usize).wrapping_sub(start);
+
+    if offset <= line.len() && line.is_char_boundary(offset) {
+        line[..offset].chars().count() + 1
+    } else {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_of() {
+        let line = "move 1 from x to 9";
+        let text = line.split(' ').nth(3).unwrap();
+        assert_eq!(column_of(line, text), 13);
+        assert_eq!(column_of(line, &String::from("x")), 1);
+    }
+
+    #[test]
+    fn test_at() {
+        let line = "addx 1x";
+        let error = ParseError::at(10, 4, line, &line[5..], "a number");
+        assert_eq!(error, ParseError::new(10, 5, 6, "1x", "a number"));
+        assert_eq!(
+            error.to_string(),
+            "Day 10 input, line 5, column 6: expected a number, found `1x`"
+        );
+    }
+}
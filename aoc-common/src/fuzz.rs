@@ -0,0 +1,213 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{catch_overflow, Answer, Error, ParseError, Rng, Solution};
+
+/// What puzzle inputs are made of, plus a few characters they never hold.
+const CHARACTERS: &[char] = &[
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'x', 'y', 'z', 'A', 'B', 'S',
+    'E', 'X', ' ', ' ', '\n', '\n', '\r', ',', '-', '+', '*', '=', ':', '[', ']', '$', '/', '.',
+    '>', '\t', 'é', '\u{0}',
+];
+
+/// Runs of text the parsers look for, inserted whole by [`mutate`].
+const FRAGMENTS: &[&str] = &[
+    "99999999999999999999",
+    "-2147483649",
+    "move ",
+    " from ",
+    "$ cd ",
+    "$ ls",
+    "dir ",
+    "addx ",
+    "noop",
+    "old",
+    " -> ",
+    "Monkey ",
+    "Sensor at x=",
+    "[]",
+    "\n\n",
+];
+
+/// Up to `length` characters of random text.
+pub fn random_text(rng: &mut Rng, length: usize) -> String {
+    (0..rng.index(length + 1))
+        .map(|_| *rng.pick(CHARACTERS))
+        .collect()
+}
+
+/// `input` with one to three random edits: characters or puzzle fragments
+/// inserted, characters removed or replaced, lines duplicated or removed, or
+/// the input cut short.
+pub fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+
+    for _ in 0..rng.range(1..=3) {
+        let at = rng.index(chars.len() + 1);
+        match rng.below(7) {
+            0 => chars.insert(at, *rng.pick(CHARACTERS)),
+            1 => {
+                let fragment = rng.pick(FRAGMENTS).chars();
+                chars.splice(at..at, fragment);
+            }
+            2 if at < chars.len() => {
+                chars.remove(at);
+            }
+            3 if at < chars.len() => chars[at] = *rng.pick(CHARACTERS),
+            4 | 5 => {
+                let text: String = chars.iter().collect();
+                let mut lines: Vec<&str> = text.split('\n').collect();
+                let line = rng.index(lines.len());
+                if rng.chance(1, 2) {
+                    lines.insert(line, lines[line]);
+                } else {
+                    lines.remove(line);
+                }
+                chars = lines.join("\n").chars().collect();
+            }
+            _ => chars.truncate(at),
+        }
+    }
+
+    chars.into_iter().collect()
+}
+
+/// Feeds `S` the valid inputs `valid` generates for each case number, random
+/// text and corrupted versions of the valid inputs, solving both parts of
+/// every input that parses.
+///
+/// Panics with the input when parsing or solving panics, a valid input is
+/// rejected, or an error is reported at a position that is not in the input.
+/// Overflows are fine, they are reported like parse errors.
+pub fn fuzz<S: Solution>(cases: u64, valid: impl Fn(u64) -> String) {
+    let mut rng = Rng::new(cases);
+
+    for case in 0..cases {
+        let input = valid(case);
+        if let Err(error) = S::parse(&input) {
+            panic!("valid input {input:?} does not parse: {error}");
+        }
+
+        let corrupted = mutate(&mut rng, &input);
+        for input in [input, random_text(&mut rng, 200), corrupted] {
+            match panic::catch_unwind(AssertUnwindSafe(|| S::parse(&input))) {
+                Ok(Ok(parsed)) => solve::<S>(&input, &parsed),
+                Ok(Err(error)) => check_position(&input, &error),
+                Err(_) => panic!("parsing {input:?} panicked"),
+            }
+        }
+    }
+}
+
+/// Solves both parts of the parsed `input`, panicking unless they either
+/// return, with or without an answer, or overflow.
+fn solve<S: Solution>(input: &str, parsed: &S::Input) {
+    let parts: [(u8, &dyn Fn() -> Option<String>); 2] = [
+        (1, &|| S::part1(parsed).answer()),
+        (2, &|| S::part2(parsed).answer()),
+    ];
+
+    for (part, answer) in parts {
+        let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+            catch_overflow(S::DAY, Some(part), || Ok(answer()))
+        }));
+        match solved {
+            Ok(Ok(_) | Err(Error::Overflow { .. })) => {}
+            Ok(Err(error)) => panic!("solving part {part} of {input:?} failed: {error}"),
+            Err(_) => panic!("solving part {part} of {input:?} panicked"),
+        }
+    }
+}
+
+/// Panics unless `error` quotes the text at its line and column, or is at the
+/// end of the input.
+fn check_position(input: &str, error: &ParseError) {
+    let lines: Vec<&str> = input.lines().collect();
+    let found = match lines.get(error.line.wrapping_sub(1)) {
+        Some(line) => {
+            error.column >= 1 && {
+                let rest: String = line.chars().skip(error.column - 1).collect();
+                error.column <= line.chars().count() + 1 && rest.starts_with(&error.text)
+            }
+        }
+        None => error.line == lines.len() + 1 && error.text.is_empty(),
+    };
+
+    assert!(found, "{error:?} is not a position in {input:?}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A number per input, with one of the bugs below planted.
+    struct Number<const BUG: u8>;
+
+    const NONE: u8 = 0;
+    /// Parsing panics on a space.
+    const PARSE: u8 = 1;
+    /// Part 2 panics on numbers of three digits or more.
+    const SOLVE: u8 = 2;
+    /// Errors point past the end of the line.
+    const POSITION: u8 = 3;
+
+    impl<const BUG: u8> Solution for Number<BUG> {
+        const DAY: u8 = 0;
+
+        type Input = u32;
+        type Output1 = u32;
+        type Output2 = u32;
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            assert!(BUG != PARSE || !input.contains(' '));
+            let mut lines = crate::Lines::new(0, input);
+            lines
+                .next_line("a number")
+                .and_then(|line| line.all(|line| line.unsigned("a number")))
+                .map_err(|error| match BUG {
+                    POSITION => ParseError::new(0, 1, 40, "", &error.expected),
+                    _ => error,
+                })
+        }
+
+        /// Overflows for the larger numbers, which is fine.
+        fn part1(input: &Self::Input) -> Self::Output1 {
+            std::hint::black_box(*input) * 100_000_000
+        }
+
+        fn part2(input: &Self::Input) -> Self::Output2 {
+            assert!(BUG != SOLVE || *input < 100);
+            *input
+        }
+    }
+
+    #[test]
+    fn test_mutate() {
+        let mut rng = Rng::new(1);
+        let mutated: Vec<String> = (0..20).map(|_| mutate(&mut rng, "12\n34\n")).collect();
+        assert!(mutated.iter().any(|input| input != "12\n34\n"));
+        assert!(random_text(&mut rng, 10).chars().count() <= 10);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        fuzz::<Number<NONE>>(50, |case| format!("{case}\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "parsing")]
+    fn test_fuzz_panic() {
+        fuzz::<Number<PARSE>>(50, |case| format!("{case}\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "solving part 2")]
+    fn test_fuzz_solver_panic() {
+        fuzz::<Number<SOLVE>>(50, |case| format!("{case}\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "not a position")]
+    fn test_fuzz_position() {
+        fuzz::<Number<POSITION>>(50, |case| format!("{case}\n"));
+    }
+}
@@ -0,0 +1,314 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Sub};
+
+use crate::{Lines, ParseError};
+
+/// Grid coordinates, `x` grows to the right and `y` grows down the rows.
+///
+/// Coordinates are signed so neighbours and offsets can be computed freely,
+/// positions outside a grid are simply not [`Grid::contains`]ed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+/// Offsets of the up, right, down and left neighbours.
+pub const NEIGHBOURS4: [Point; 4] = [
+    Point::new(0, -1),
+    Point::new(1, 0),
+    Point::new(0, 1),
+    Point::new(-1, 0),
+];
+
+/// Offsets of all eight surrounding cells, clockwise from the top left.
+pub const NEIGHBOURS8: [Point; 8] = [
+    Point::new(-1, -1),
+    Point::new(0, -1),
+    Point::new(1, -1),
+    Point::new(1, 0),
+    Point::new(1, 1),
+    Point::new(0, 1),
+    Point::new(-1, 1),
+    Point::new(-1, 0),
+];
+
+/// Rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Returns `None` when the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses one row per line with `cell`, which returns `None` for
+    /// characters that are not `expected`.
+    pub fn parse(
+        day: u8,
+        input: &str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+
+        for mut line in Lines::new(day, input) {
+            let start = cells.len();
+            while !line.is_empty() {
+                let mut value = None;
+                line.satisfy(
+                    |ch| {
+                        value = cell(ch);
+                        value.is_some()
+                    },
+                    expected,
+                )?;
+                cells.extend(value);
+            }
+
+            let length = cells.len() - start;
+            if *width.get_or_insert(length) != length {
+                return Err(line.error_at(line.line(), "a rectangular grid"));
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(ParseError::eof(day, input, expected)),
+        }
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (0..self.width as i32).contains(&point.x) && (0..self.height as i32).contains(&point.y)
+    }
+
+    /// Row-major index of `point`, also usable as a node id.
+    pub fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.y as usize * self.width + point.x as usize)
+    }
+
+    pub fn point_of(&self, index: usize) -> Point {
+        Point::new((index % self.width) as i32, (index / self.width) as i32)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    /// Every point of the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.cells.len()).map(|index| self.point_of(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|index| self.point_of(index))
+    }
+
+    /// Up, right, down and left neighbours of `point` that are inside the grid.
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &NEIGHBOURS4)
+    }
+
+    /// All surrounding cells of `point` that are inside the grid.
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &NEIGHBOURS8)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        offsets: &'static [Point],
+    ) -> impl Iterator<Item = Point> + 'a {
+        offsets
+            .iter()
+            .map(move |offset| point + *offset)
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(
+            x < self.width,
+            "column {x} outside a grid {} wide",
+            self.width
+        );
+        self.rows().map(move |row| &row[x])
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.get(point) {
+            Some(cell) => cell,
+            None => panic!("{point:?} outside a {}x{} grid", self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_mut(point) {
+            Some(cell) => cell,
+            None => panic!("{point:?} outside a {width}x{height} grid"),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(0, input, "a digit", |ch| ch.to_digit(10))
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Point::new(2, 1)], 6);
+        assert_eq!(grid.get(Point::new(3, 1)), None);
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        assert_eq!(
+            digits("123\n4x6\n"),
+            Err(ParseError::new(0, 2, 2, "x", "a digit"))
+        );
+        assert_eq!(
+            digits("123\n45\n"),
+            Err(ParseError::new(0, 2, 1, "45", "a rectangular grid"))
+        );
+        assert_eq!(digits(""), Err(ParseError::new(0, 1, 1, "", "a digit")));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        let corner: Vec<Point> = grid.neighbours4(Point::new(0, 0)).collect();
+        assert_eq!(corner, [Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.neighbours8(Point::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbours4(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = digits("123\n456\n789\n").unwrap();
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), [3, 6, 9]);
+        assert_eq!(grid.position(|cell| *cell == 8), Some(Point::new(1, 2)));
+        assert_eq!(grid.index_of(Point::new(1, 2)), Some(7));
+        assert_eq!(grid.point_of(7), Point::new(1, 2));
+        assert_eq!(Grid::from_rows(vec![vec![1, 2], vec![3]]), None);
+    }
+}
@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::Path;
+
+use crate::{Error, Grid, Result};
+
+pub type Rgb = [u8; 3];
+
+/// A grid state mapped through a palette, one pixel per cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Image {
+    Grey(Grid<u8>),
+    Colour(Grid<Rgb>),
+}
+
+impl Image {
+    /// Netpbm encoding with every cell drawn as a `scale` by `scale` square,
+    /// binary PGM (`P5`) for grey images and binary PPM (`P6`) for colour.
+    pub fn encode(&self, scale: usize) -> Vec<u8> {
+        match self {
+            Self::Grey(grid) => encode(grid, scale, "P5", |grey| [*grey]),
+            Self::Colour(grid) => encode(grid, scale, "P6", |rgb| *rgb),
+        }
+    }
+
+    /// `.pgm` or `.ppm`, matching [`Image::encode`].
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Grey(_) => "pgm",
+            Self::Colour(_) => "ppm",
+        }
+    }
+}
+
+fn encode<T, const N: usize>(
+    grid: &Grid<T>,
+    scale: usize,
+    magic: &str,
+    pixel: impl Fn(&T) -> [u8; N],
+) -> Vec<u8> {
+    let scale = scale.max(1);
+    let (width, height) = (grid.width() * scale, grid.height() * scale);
+
+    let mut bytes = format!("{magic}\n{width} {height}\n255\n").into_bytes();
+    bytes.reserve(width * height * N);
+    for row in grid.rows() {
+        let mut line = Vec::with_capacity(width * N);
+        for cell in row {
+            for _ in 0..scale {
+                line.extend(pixel(cell));
+            }
+        }
+        for _ in 0..scale {
+            bytes.extend(&line);
+        }
+    }
+
+    bytes
+}
+
+pub fn write_image<P: AsRef<Path>>(path: P, image: &Image, scale: usize) -> Result<()> {
+    let path = path.as_ref();
+
+    fs::write(path, image.encode(scale))
+        .map_err(|error| Error::Write(path.display().to_string(), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_grey() {
+        let grid = Grid::from_rows(vec![vec![0, 255]]).unwrap();
+        let image = Image::Grey(grid);
+        assert_eq!(image.encode(1), b"P5\n2 1\n255\n\x00\xff");
+        assert_eq!(
+            image.encode(2),
+            b"P5\n4 2\n255\n\x00\x00\xff\xff\x00\x00\xff\xff"
+        );
+        assert_eq!(image.extension(), "pgm");
+    }
+
+    #[test]
+    fn test_encode_colour() {
+        let grid = Grid::from_rows(vec![vec![[1, 2, 3]], vec![[4, 5, 6]]]).unwrap();
+        let image = Image::Colour(grid);
+        assert_eq!(image.encode(1), b"P6\n1 2\n255\n\x01\x02\x03\x04\x05\x06");
+        assert_eq!(image.extension(), "ppm");
+    }
+}
@@ -0,0 +1,92 @@
+use std::fs::File;
+use std::io::{self, prelude::*, BufReader};
+use std::path::Path;
+
+mod answers;
+mod bench;
+mod bigint;
+mod checked;
+mod debug;
+mod error;
+mod fuzz;
+mod grid;
+mod image;
+mod json;
+mod parse;
+mod rng;
+mod solution;
+mod visual;
+
+pub use answers::{
+    input_hash, read_answers, run_day, write_answers, Answers, HashingReader, Verdict,
+    DEFAULT_ANSWERS,
+};
+pub use bench::{bench, change, read_baseline, write_baseline, Baseline, Stats, Timings, PHASES};
+pub use bigint::BigUint;
+pub use checked::catch_overflow;
+pub use debug::{debug, Comparison, Condition, DebugCommand, Debugger, DEBUG_HELP};
+pub use error::{Error, ParseError};
+pub use fuzz::{fuzz, mutate, random_text};
+pub use grid::{Grid, Point, NEIGHBOURS4, NEIGHBOURS8};
+pub use image::{write_image, Image, Rgb};
+pub use json::Json;
+pub use parse::{stream_lines, Line, Lines};
+pub use rng::Rng;
+pub use solution::{solve, solve_stream, Answer, Solution, Streaming};
+pub use visual::{export, frames, play, Frames, Simulation};
+
+pub const DEFAULT_INPUT: &str = "input/input.txt";
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Reads `input/input.txt` relative to the current working directory.
+pub fn read_input() -> Result<String> {
+    read_input_from(DEFAULT_INPUT)
+}
+
+pub fn read_input_from<P: AsRef<Path>>(path: P) -> Result<String> {
+    let path = path.as_ref();
+
+    read_from(&path.display().to_string(), open_input_from(path)?)
+}
+
+pub fn read_stdin() -> Result<String> {
+    read_from("<stdin>", io::stdin().lock())
+}
+
+/// Opens `path` for reading, buffered so it can be streamed line by line.
+pub fn open_input_from<P: AsRef<Path>>(path: P) -> Result<BufReader<File>> {
+    let path = path.as_ref();
+
+    match File::open(path) {
+        Ok(file) => Ok(BufReader::new(file)),
+        Err(error) => Err(Error::Open(path.display().to_string(), error)),
+    }
+}
+
+/// Reads all of `reader`, `name` is the file it reads for error messages.
+pub fn read_from<R: Read>(name: &str, mut reader: R) -> Result<String> {
+    let mut contents = String::with_capacity(10000);
+
+    match reader.read_to_string(&mut contents) {
+        Ok(_) => Ok(contents),
+        Err(error) => Err(Error::Read(name.to_string(), error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_missing_file() {
+        let error = read_input_from("does/not/exist.txt").unwrap_err();
+        assert!(matches!(error, Error::Open(path, _) if path == "does/not/exist.txt"));
+    }
+
+    #[test]
+    fn test_read_manifest() {
+        let contents = read_input_from(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"));
+        assert!(contents.unwrap().contains("aoc-common"));
+    }
+}
@@ -0,0 +1,413 @@
+use std::io::BufRead;
+use std::str::FromStr;
+
+use crate::{Error, ParseError};
+
+/// Cursor over a single line of puzzle input.
+///
+/// Every parser consumes from the front of the line and errors point at the
+/// column it stopped at, quoting the token found there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Line<'a> {
+    day: u8,
+    index: usize,
+    line: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// `index` is the 0-based line index as given by `lines().enumerate()`.
+    pub fn new(day: u8, index: usize, line: &'a str) -> Self {
+        Self {
+            day,
+            index,
+            line,
+            rest: line,
+        }
+    }
+
+    /// The whole line, consumed or not.
+    pub fn line(&self) -> &'a str {
+        self.line
+    }
+
+    /// The part of the line not consumed yet.
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    /// Error pointing at the token at the current column.
+    pub fn error(&self, expected: &str) -> ParseError {
+        self.error_at(self.token(), expected)
+    }
+
+    /// Error pointing at `text`, which must be a slice of the line.
+    pub fn error_at(&self, text: &str, expected: &str) -> ParseError {
+        ParseError::at(self.day, self.index, self.line, text, expected)
+    }
+
+    /// The run of letters and digits at the current column, or the single
+    /// character there when it is neither.
+    fn token(&self) -> &'a str {
+        let end = match self
+            .rest
+            .char_indices()
+            .find(|(_, ch)| !ch.is_alphanumeric())
+        {
+            Some((0, ch)) => ch.len_utf8(),
+            Some((end, _)) => end,
+            None => self.rest.len(),
+        };
+        &self.rest[..end]
+    }
+
+    fn advance(&mut self, length: usize) -> &'a str {
+        let (taken, rest) = self.rest.split_at(length);
+        self.rest = rest;
+        taken
+    }
+
+    /// Consumes `text` if the line continues with it.
+    pub fn eat(&mut self, text: &str) -> bool {
+        let found = self.rest.starts_with(text);
+        if found {
+            self.advance(text.len());
+        }
+        found
+    }
+
+    pub fn literal(&mut self, text: &str) -> Result<(), ParseError> {
+        if self.eat(text) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("`{text}`")))
+        }
+    }
+
+    /// Consumes a single character matching `predicate`.
+    pub fn satisfy(
+        &mut self,
+        predicate: impl FnOnce(char) -> bool,
+        expected: &str,
+    ) -> Result<char, ParseError> {
+        match self.rest.chars().next() {
+            Some(ch) if predicate(ch) => {
+                self.advance(ch.len_utf8());
+                Ok(ch)
+            }
+            Some(ch) => Err(self.error_at(&self.rest[..ch.len_utf8()], expected)),
+            None => Err(self.error_at(self.rest, expected)),
+        }
+    }
+
+    /// Consumes characters while `predicate` holds, possibly none.
+    pub fn take_while(&mut self, mut predicate: impl FnMut(char) -> bool) -> &'a str {
+        let end = self
+            .rest
+            .char_indices()
+            .find(|(_, ch)| !predicate(*ch))
+            .map_or(self.rest.len(), |(end, _)| end);
+        self.advance(end)
+    }
+
+    /// Consumes the rest of the line.
+    pub fn take_rest(&mut self) -> &'a str {
+        self.advance(self.rest.len())
+    }
+
+    /// Parses the token at the current column as an unsigned number.
+    pub fn unsigned<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let token = self.token();
+        if !token.starts_with(|ch: char| ch.is_ascii_digit()) {
+            return Err(self.error(expected));
+        }
+        let number = token.parse().map_err(|_| self.error(expected))?;
+        self.advance(token.len());
+        Ok(number)
+    }
+
+    /// Like [`Line::unsigned`] with an optional leading `-`.
+    pub fn signed<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let mut after_sign = *self;
+        if !after_sign.eat("-") {
+            return self.unsigned(expected);
+        }
+        let token = after_sign.token();
+        if !token.starts_with(|ch: char| ch.is_ascii_digit()) {
+            return Err(self.error(expected));
+        }
+
+        let text = &self.rest[..token.len() + 1];
+        let number = text.parse().map_err(|_| self.error_at(text, expected))?;
+        self.advance(text.len());
+        Ok(number)
+    }
+
+    /// Consumes the first of `choices` the line continues with.
+    pub fn one_of<T: Clone>(
+        &mut self,
+        choices: &[(&str, T)],
+        expected: &str,
+    ) -> Result<T, ParseError> {
+        for (text, value) in choices {
+            if self.eat(text) {
+                return Ok(value.clone());
+            }
+        }
+        Err(self.error(expected))
+    }
+
+    /// Runs `parser`, leaving the line untouched when it fails.
+    pub fn attempt<T>(
+        &mut self,
+        parser: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Option<T> {
+        let mut attempt = *self;
+        let parsed = parser(&mut attempt).ok()?;
+        *self = attempt;
+        Some(parsed)
+    }
+
+    /// One or more `item`s with `separator` between them.
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![item(self)?];
+        while self.eat(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Fails unless the whole line has been consumed.
+    pub fn end(&self) -> Result<(), ParseError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error("the end of the line"))
+        }
+    }
+
+    /// Runs `parser` on the line and checks it consumed all of it.
+    pub fn all<T>(
+        mut self,
+        parser: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let parsed = parser(&mut self)?;
+        self.end()?;
+        Ok(parsed)
+    }
+}
+
+/// Cursor over the lines of a puzzle input, or of one block of it.
+#[derive(Debug, Clone)]
+pub struct Lines<'a> {
+    day: u8,
+    lines: Vec<(usize, &'a str)>,
+    next: usize,
+    end: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(day: u8, input: &'a str) -> Self {
+        let lines: Vec<(usize, &str)> = input.lines().enumerate().collect();
+        let end = lines.len();
+
+        Self {
+            day,
+            lines,
+            next: 0,
+            end,
+        }
+    }
+
+    /// Error for lines that ended while `expected` was still missing, pointing
+    /// just past the last line.
+    pub fn eof(&self, expected: &str) -> ParseError {
+        ParseError::new(self.day, self.end + 1, 1, "", expected)
+    }
+
+    /// The next line, or an [`Lines::eof`] error when there is none.
+    pub fn next_line(&mut self, expected: &str) -> Result<Line<'a>, ParseError> {
+        self.next().ok_or_else(|| self.eof(expected))
+    }
+
+    /// Parses every remaining line with `parser`, which must consume it all.
+    pub fn parse_each<T>(
+        self,
+        mut parser: impl FnMut(&mut Line<'a>) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.map(|line| line.all(&mut parser)).collect()
+    }
+
+    /// Splits the remaining lines into blocks separated by blank lines.
+    pub fn blocks(self) -> impl Iterator<Item = Lines<'a>> {
+        let day = self.day;
+        let mut lines = self.lines.into_iter().skip(self.next).peekable();
+
+        std::iter::from_fn(move || {
+            while lines.next_if(|(_, line)| line.trim().is_empty()).is_some() {}
+            let mut block = Vec::new();
+            while let Some(line) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+                block.push(line);
+            }
+
+            let end = block.last()?.0 + 1;
+            Some(Lines {
+                day,
+                lines: block,
+                next: 0,
+                end,
+            })
+        })
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Line<'a>> {
+        let (index, line) = *self.lines.get(self.next)?;
+        self.next += 1;
+        Some(Line::new(self.day, index, line))
+    }
+}
+
+/// Feeds the lines of `reader` to `f` as they are read, holding only one of
+/// them in memory. Returns the number of lines read.
+pub fn stream_lines<R: BufRead>(
+    day: u8,
+    mut reader: R,
+    mut f: impl FnMut(Line<'_>) -> Result<(), ParseError>,
+) -> crate::Result<usize> {
+    let mut text = String::new();
+    let mut index = 0;
+
+    loop {
+        text.clear();
+        match reader.read_line(&mut text) {
+            Ok(0) => return Ok(index),
+            Ok(_) => {}
+            Err(error) => return Err(Error::Read(String::from("<input>"), error)),
+        }
+
+        let line = match text.strip_suffix('\n') {
+            Some(line) => line.strip_suffix('\r').unwrap_or(line),
+            None => &text,
+        };
+        f(Line::new(day, index, line))?;
+        index += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line() {
+        let mut line = Line::new(15, 2, "x=-3, y=12: ok");
+        line.literal("x=").unwrap();
+        assert_eq!(line.signed::<i32>("a coordinate"), Ok(-3));
+        line.literal(", y=").unwrap();
+        assert_eq!(line.signed::<i32>("a coordinate"), Ok(12));
+        assert!(!line.eat(";"));
+        assert_eq!(
+            line.end(),
+            Err(ParseError::new(15, 3, 11, ":", "the end of the line"))
+        );
+        assert_eq!(line.take_rest(), ": ok");
+        assert_eq!(line.end(), Ok(()));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Line::new(7, 0, "12k a.txt").unsigned::<u32>("a file size"),
+            Err(ParseError::new(7, 1, 1, "12k", "a file size"))
+        );
+        assert_eq!(
+            Line::new(7, 0, "-x").signed::<i32>("a number"),
+            Err(ParseError::new(7, 1, 1, "-", "a number"))
+        );
+        assert_eq!(
+            Line::new(3, 0, "1d").satisfy(|ch| ch.is_ascii_alphabetic(), "an item letter"),
+            Err(ParseError::new(3, 1, 1, "1", "an item letter"))
+        );
+        assert_eq!(
+            Line::new(7, 0, "cd").literal("ls"),
+            Err(ParseError::new(7, 1, 1, "cd", "`ls`"))
+        );
+        assert_eq!(
+            Line::new(7, 0, "W").one_of(&[("X", 0), ("Y", 1)], "X or Y"),
+            Err(ParseError::new(7, 1, 1, "W", "X or Y"))
+        );
+    }
+
+    #[test]
+    fn test_combinators() {
+        let mut line = Line::new(11, 0, "79, 98, 3");
+        let items = line.separated(", ", |line| line.unsigned::<u32>("a worry level"));
+        assert_eq!(items, Ok(vec![79, 98, 3]));
+
+        let mut line = Line::new(10, 0, "addx 3");
+        assert_eq!(line.attempt(|line| line.literal("noop")), None);
+        assert_eq!(line.rest(), "addx 3");
+        let value = line.attempt(|line| {
+            line.literal("addx ")?;
+            line.signed::<i32>("a value")
+        });
+        assert_eq!(value, Some(3));
+        assert!(line.is_empty());
+    }
+
+    #[test]
+    fn test_lines() {
+        let input = "1\n2\n\n\n3\nx\n";
+        let mut blocks = Lines::new(1, input).blocks();
+
+        let mut first = blocks.next().unwrap();
+        assert_eq!(first.next_line("a number").unwrap().line(), "1");
+        assert_eq!(first.next_line("a number").unwrap().line(), "2");
+        assert_eq!(
+            first.next_line("a number"),
+            Err(ParseError::new(1, 3, 1, "", "a number"))
+        );
+
+        let second = blocks.next().unwrap();
+        assert_eq!(
+            second.parse_each(|line| line.unsigned::<u32>("a number")),
+            Err(ParseError::new(1, 6, 1, "x", "a number"))
+        );
+        assert!(blocks.next().is_none());
+        assert_eq!(
+            Lines::new(10, "").next_line("an instruction"),
+            Err(ParseError::new(10, 1, 1, "", "an instruction"))
+        );
+    }
+
+    #[test]
+    fn test_stream_lines() {
+        let mut seen = Vec::new();
+        let count = stream_lines(1, "1\r\n\n3".as_bytes(), |line| {
+            seen.push(line.line().to_string());
+            Ok(())
+        });
+        assert_eq!(count.unwrap(), 3);
+        assert_eq!(seen, ["1", "", "3"]);
+
+        let failed = stream_lines(1, "1\nx\n".as_bytes(), |line| {
+            line.all(|line| line.unsigned::<u32>("a number")).map(drop)
+        });
+        assert!(matches!(
+            failed,
+            Err(Error::Parse(error)) if error == ParseError::new(1, 2, 1, "x", "a number")
+        ));
+    }
+}
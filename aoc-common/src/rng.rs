@@ -0,0 +1,93 @@
+use std::ops::RangeInclusive;
+
+/// Small deterministic random number generator (SplitMix64), so a seed always
+/// generates the same input.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..bound`, which must not be empty.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "empty range 0..0");
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    /// Uniform in `range`, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {start}..={end}");
+        let span = end.abs_diff(start).wrapping_add(1);
+        if span == 0 {
+            // the whole of i64
+            return self.next_u64() as i64;
+        }
+        start.wrapping_add(self.below(span) as i64)
+    }
+
+    /// Uniform index into a slice of `len` items.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// `true` with probability `numerator / denominator`.
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeatable() {
+        let first: Vec<u64> = (0..5)
+            .scan(Rng::new(7), |rng, _| Some(rng.next_u64()))
+            .collect();
+        let again: Vec<u64> = (0..5)
+            .scan(Rng::new(7), |rng, _| Some(rng.next_u64()))
+            .collect();
+        assert_eq!(first, again);
+        assert_ne!(Rng::new(7).next_u64(), Rng::new(8).next_u64());
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.range(-3..=3)));
+            assert!(rng.below(10) < 10);
+        }
+        assert_eq!(rng.range(5..=5), 5);
+        rng.range(i64::MIN..=i64::MAX);
+
+        let mut items = [1, 2, 3, 4, 5];
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, [1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,162 @@
+use std::io::BufRead;
+
+use crate::{catch_overflow, read_from, Error, ParseError};
+
+/// A single day's puzzle: one parse step shared by both parts.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+    type Output1: Answer;
+    type Output2: Answer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Self::Output1;
+
+    fn part2(input: &Self::Input) -> Self::Output2;
+
+    /// Reads all of `reader` and parses it.
+    fn parse_reader<R: BufRead>(reader: R) -> crate::Result<Self::Input> {
+        let input = read_from("<input>", reader)?;
+        Ok(Self::parse(&input)?)
+    }
+}
+
+/// A day whose answers can both be worked out in one pass over the input,
+/// without holding all of it in memory.
+pub trait Streaming: Solution {
+    fn stream<R: BufRead>(reader: R) -> crate::Result<(Self::Output1, Self::Output2)>;
+}
+
+/// What a part returns. Parts some inputs have no answer to, like a shortest
+/// path where there is no path, return an `Option`.
+pub trait Answer {
+    /// The answer as printed, `None` when there is none.
+    fn answer(&self) -> Option<String>;
+}
+
+macro_rules! impl_answer {
+    ($($type:ty),*) => {
+        $(impl Answer for $type {
+            fn answer(&self) -> Option<String> {
+                Some(self.to_string())
+            }
+        })*
+    };
+}
+
+impl_answer!(i32, i64, u32, u64, usize, String);
+
+impl<T: Answer> Answer for Option<T> {
+    fn answer(&self) -> Option<String> {
+        self.as_ref()?.answer()
+    }
+}
+
+/// The printed `answer` to `part` of `day`, an error when there is none.
+fn found(day: u8, part: u8, answer: impl Answer) -> Result<String, Error> {
+    answer.answer().ok_or(Error::NoAnswer { day, part })
+}
+
+/// Parses `input` and solves `part` of `S`, returning the formatted answer.
+/// Overflows are reported as errors where overflow checks are on.
+pub fn solve<S: Solution>(input: &str, part: u8) -> Result<String, Error> {
+    catch_overflow(S::DAY, Some(part), || {
+        let input = S::parse(input)?;
+
+        match part {
+            1 => found(S::DAY, part, S::part1(&input)),
+            2 => found(S::DAY, part, S::part2(&input)),
+            _ => Err(Error::NoSolver { day: S::DAY, part }),
+        }
+    })
+}
+
+/// Streams `reader` through `S`, returning both formatted answers.
+pub fn solve_stream<S: Streaming, R: BufRead>(reader: R) -> Result<[String; 2], Error> {
+    catch_overflow(S::DAY, None, || {
+        let (part1, part2) = S::stream(reader)?;
+        Ok([found(S::DAY, 1, part1)?, found(S::DAY, 2, part2)?])
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 0;
+
+        type Input = Vec<i32>;
+        type Output1 = i32;
+        type Output2 = Option<String>;
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            crate::Line::new(0, 0, input)
+                .all(|line| line.separated(",", |line| line.signed("a number")))
+        }
+
+        fn part1(input: &Self::Input) -> Self::Output1 {
+            input.iter().sum()
+        }
+
+        fn part2(input: &Self::Input) -> Self::Output2 {
+            input
+                .iter()
+                .all(|number| *number >= 0)
+                .then(|| format!("{:?}", input))
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve::<Sum>("1,2,3", 1).unwrap(), "6");
+        assert_eq!(solve::<Sum>("1,2,3", 2).unwrap(), "[1, 2, 3]");
+        assert!(matches!(
+            solve::<Sum>("1,2,3", 3),
+            Err(Error::NoSolver { day: 0, part: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_solve_no_answer() {
+        assert!(matches!(
+            solve::<Sum>("1,-2", 2),
+            Err(Error::NoAnswer { day: 0, part: 2 })
+        ));
+        assert_eq!(
+            Error::NoAnswer { day: 0, part: 2 }.to_string(),
+            "Day 0 part 2 has no answer"
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_solve_overflow() {
+        let input = format!("{},1", i32::MAX);
+        assert!(matches!(
+            solve::<Sum>(&input, 1),
+            Err(Error::Overflow {
+                day: 0,
+                part: Some(1),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_reader() {
+        assert_eq!(Sum::parse_reader("1,2,3".as_bytes()).unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_solve_parse_error() {
+        let Err(Error::Parse(error)) = solve::<Sum>("1,x,3", 1) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error, ParseError::new(0, 1, 3, "x", "a number"));
+    }
+}
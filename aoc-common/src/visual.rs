@@ -0,0 +1,138 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+/// A puzzle simulation that can be advanced and drawn one step at a time.
+pub trait Simulation {
+    /// Advances the simulation, returns `false` once it has finished.
+    fn step(&mut self) -> bool;
+
+    /// Text drawing of the current state.
+    fn frame(&self) -> String;
+
+    /// Named values of the current state, for the debugger to print and
+    /// stop on.
+    fn variables(&self) -> Vec<(&'static str, i64)> {
+        Vec::new()
+    }
+}
+
+/// Frames of `simulation`: the initial state, every `every`th step and the
+/// final state.
+pub fn frames(simulation: &mut dyn Simulation, every: usize) -> Frames<'_> {
+    Frames {
+        simulation,
+        every: every.max(1),
+        started: false,
+        finished: false,
+    }
+}
+
+pub struct Frames<'a> {
+    simulation: &'a mut dyn Simulation,
+    every: usize,
+    started: bool,
+    finished: bool,
+}
+
+impl Iterator for Frames<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if !self.started {
+            self.started = true;
+            return Some(self.simulation.frame());
+        }
+        if self.finished {
+            return None;
+        }
+
+        let mut stepped = 0;
+        while stepped < self.every && self.simulation.step() {
+            stepped += 1;
+        }
+        self.finished = stepped < self.every;
+
+        (stepped > 0).then(|| self.simulation.frame())
+    }
+}
+
+/// Plays `simulation` in the terminal, redrawing the screen `fps` times a
+/// second.
+pub fn play(simulation: &mut dyn Simulation, every: usize, fps: u32) -> io::Result<()> {
+    let delay = Duration::from_secs(1) / fps.max(1);
+    let mut out = io::stdout().lock();
+
+    for frame in frames(simulation, every) {
+        // move to the top left and clear the screen
+        write!(out, "\x1b[H\x1b[2J{frame}")?;
+        out.flush()?;
+        thread::sleep(delay);
+    }
+    writeln!(out)
+}
+
+/// Writes the frames of `simulation` to numbered text files in `dir`,
+/// returning how many were written.
+pub fn export<P: AsRef<Path>>(
+    simulation: &mut dyn Simulation,
+    every: usize,
+    dir: P,
+) -> Result<usize> {
+    let dir = dir.as_ref();
+    let failed = |error| Error::Write(dir.display().to_string(), error);
+    fs::create_dir_all(dir).map_err(failed)?;
+
+    let mut count = 0;
+    for frame in frames(simulation, every) {
+        let path = dir.join(format!("frame-{count:06}.txt"));
+        fs::write(&path, frame).map_err(|error| Error::Write(path.display().to_string(), error))?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Countdown(u32);
+
+    impl Simulation for Countdown {
+        fn step(&mut self) -> bool {
+            if self.0 == 0 {
+                return false;
+            }
+            self.0 -= 1;
+            true
+        }
+
+        fn frame(&self) -> String {
+            self.0.to_string()
+        }
+    }
+
+    #[test]
+    fn test_frames() {
+        let all: Vec<String> = frames(&mut Countdown(3), 1).collect();
+        assert_eq!(all, ["3", "2", "1", "0"]);
+        let sparse: Vec<String> = frames(&mut Countdown(5), 2).collect();
+        assert_eq!(sparse, ["5", "3", "1", "0"]);
+    }
+
+    #[test]
+    fn test_export() {
+        let dir = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        assert_eq!(export(&mut Countdown(2), 1, &dir).unwrap(), 3);
+        assert_eq!(
+            fs::read_to_string(dir.join("frame-000001.txt")).unwrap(),
+            "1"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
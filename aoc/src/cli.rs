@@ -0,0 +1,602 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const USAGE: &str =
+    "Usage: aoc run --day N [--part P] [--input PATH] [--answers PATH] [--record] [--json]
+       aoc run --all [--jobs J] [--answers PATH] [--json]
+       aoc bench [--day N] [--runs R] [--baseline PATH] [--save PATH]
+       aoc animate --day N [--part P] [--input PATH] [--fps F] [--every K] [--export DIR]
+       aoc debug --day N [--part P] --input PATH
+       aoc image --day N [--part P] [--input PATH] [--scale S] [--output PATH]
+       aoc new --day N
+       aoc generate --day N [--seed S] [--size N] [--output PATH]
+
+Run options:
+    --day N           Day to run
+    --part P          Part to run (1 or 2), runs both parts when omitted
+    --input PATH      Puzzle input file, `-` or omitted reads from stdin
+    --answers PATH    Answer file to check against, defaults to `answers.txt`
+    --record          Add answers not yet in the answer file to it
+    --json            Print the results as a JSON array
+    --all             Run both parts of every day on its `dayN/input/input.txt`
+                      in parallel and print a summary table, run from the
+                      workspace root
+    --jobs J          Threads for `--all`, defaults to one per core
+
+Bench options:
+    --day N           Day to benchmark, benchmarks every day when omitted
+    --runs R          Timed runs per phase, defaults to 10
+    --baseline PATH   Compare medians against a saved baseline
+    --save PATH       Save the medians as a baseline
+
+Animate options:
+    --day N           Day to animate (9, 10 or 14)
+    --part P          Part to animate, defaults to 1
+    --input PATH      Puzzle input file, `-` or omitted reads from stdin
+    --fps F           Frames per second, defaults to 20
+    --every K         Draw every Kth step only, defaults to 1
+    --export DIR      Write the frames to text files in DIR instead of playing them
+
+Debug options:
+    --day N           Day to step through (9, 10 or 14), reading commands
+                      from stdin; `help` lists them
+    --part P          Part to debug, defaults to 1
+    --input PATH      Puzzle input file
+
+Image options:
+    --day N           Day to draw (8, 9, 12 or 14)
+    --part P          Part whose final state is drawn, defaults to 1
+    --input PATH      Puzzle input file, `-` or omitted reads from stdin
+    --scale S         Pixels per grid cell, defaults to 1
+    --output PATH     Image file, defaults to `dayN-partP.pgm` or `.ppm`
+
+New options:
+    --day N           Day to create the `dayN` crate for, run from the workspace root
+
+Generate options:
+    --day N           Day to generate an input for
+    --seed S          Seed of the random input, defaults to 0
+    --size N          Elves, rounds, grid size, ... defaults to the puzzle input's
+    --output PATH     Input file to write, prints the input when omitted";
+
+pub const DEFAULT_ANSWERS: &str = "answers.txt";
+pub const DEFAULT_RUNS: usize = 10;
+pub const DEFAULT_FPS: u32 = 20;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    RunAll(RunAllArgs),
+    Bench(BenchArgs),
+    Animate(AnimateArgs),
+    Debug(DebugArgs),
+    Image(ImageArgs),
+    New(NewArgs),
+    Generate(GenerateArgs),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunArgs {
+    pub day: u8,
+    pub part: Option<u8>,
+    pub input: Input,
+    pub answers: PathBuf,
+    pub record: bool,
+    pub json: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunAllArgs {
+    pub jobs: Option<usize>,
+    pub answers: PathBuf,
+    pub json: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    Stdin,
+    Path(PathBuf),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchArgs {
+    pub day: Option<u8>,
+    pub runs: usize,
+    pub baseline: Option<PathBuf>,
+    pub save: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AnimateArgs {
+    pub day: u8,
+    pub part: u8,
+    pub input: Input,
+    pub fps: u32,
+    pub every: usize,
+    pub export: Option<PathBuf>,
+}
+
+/// The input is a file, stdin is left for the debugger's commands.
+#[derive(Debug, PartialEq)]
+pub struct DebugArgs {
+    pub day: u8,
+    pub part: u8,
+    pub input: PathBuf,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImageArgs {
+    pub day: u8,
+    pub part: u8,
+    pub input: Input,
+    pub scale: usize,
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NewArgs {
+    pub day: u8,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenerateArgs {
+    pub day: u8,
+    pub seed: u64,
+    pub size: Option<usize>,
+    pub output: Option<PathBuf>,
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        Some("run") => parse_run(args),
+        Some("bench") => parse_bench(args),
+        Some("animate") => parse_animate(args),
+        Some("debug") => parse_debug(args),
+        Some("image") => parse_image(args),
+        Some("new") => parse_new(args),
+        Some("generate") => parse_generate(args),
+        Some("help" | "-h" | "--help") | None => Ok(Command::Help),
+        Some(other) => Err(format!("Unknown command `{other}`")),
+    }
+}
+
+fn parse_run<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut input = Input::Stdin;
+    let mut answers = PathBuf::from(DEFAULT_ANSWERS);
+    let mut record = false;
+    let mut json = false;
+    let mut all = false;
+    let mut jobs = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "--part" => part = Some(parse_number(&arg, args.next())?),
+            "--input" => input = parse_input(&arg, args.next())?,
+            "--answers" => answers = parse_path(&arg, args.next())?,
+            "--record" => record = true,
+            "--json" => json = true,
+            "--all" => all = true,
+            "--jobs" => jobs = Some(parse_number(&arg, args.next())?),
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown argument `{other}`")),
+        }
+    }
+
+    if all {
+        if day.is_some() || part.is_some() || input != Input::Stdin || record {
+            return Err(String::from(
+                "`--all` runs every day's own input, it cannot take `--day`, `--part`, \
+                 `--input` or `--record`",
+            ));
+        }
+        if jobs == Some(0) {
+            return Err(String::from("`--jobs` must be at least 1"));
+        }
+        return Ok(Command::RunAll(RunAllArgs {
+            jobs,
+            answers,
+            json,
+        }));
+    }
+    if jobs.is_some() {
+        return Err(String::from("`--jobs` only applies to `--all`"));
+    }
+
+    let Some(day) = day else {
+        return Err(String::from("Missing required argument `--day`"));
+    };
+
+    Ok(Command::Run(RunArgs {
+        day,
+        part,
+        input,
+        answers,
+        record,
+        json,
+    }))
+}
+
+fn parse_bench<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut bench = BenchArgs {
+        day: None,
+        runs: DEFAULT_RUNS,
+        baseline: None,
+        save: None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => bench.day = Some(parse_number(&arg, args.next())?),
+            "--runs" => bench.runs = parse_number(&arg, args.next())?,
+            "--baseline" => bench.baseline = Some(parse_path(&arg, args.next())?),
+            "--save" => bench.save = Some(parse_path(&arg, args.next())?),
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown argument `{other}`")),
+        }
+    }
+
+    if bench.runs == 0 {
+        return Err(String::from("`--runs` must be at least 1"));
+    }
+
+    Ok(Command::Bench(bench))
+}
+
+fn parse_animate<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut day = None;
+    let mut part = 1;
+    let mut input = Input::Stdin;
+    let mut fps = DEFAULT_FPS;
+    let mut every = 1;
+    let mut export = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "--part" => part = parse_number(&arg, args.next())?,
+            "--input" => input = parse_input(&arg, args.next())?,
+            "--fps" => fps = parse_number(&arg, args.next())?,
+            "--every" => every = parse_number(&arg, args.next())?,
+            "--export" => export = Some(parse_path(&arg, args.next())?),
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown argument `{other}`")),
+        }
+    }
+
+    let Some(day) = day else {
+        return Err(String::from("Missing required argument `--day`"));
+    };
+    if fps == 0 || every == 0 {
+        return Err(String::from("`--fps` and `--every` must be at least 1"));
+    }
+
+    Ok(Command::Animate(AnimateArgs {
+        day,
+        part,
+        input,
+        fps,
+        every,
+        export,
+    }))
+}
+
+fn parse_debug<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut day = None;
+    let mut part = 1;
+    let mut input = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "--part" => part = parse_number(&arg, args.next())?,
+            "--input" => match parse_input(&arg, args.next())? {
+                Input::Path(path) => input = Some(path),
+                Input::Stdin => {
+                    return Err(String::from(
+                        "`--input` must be a file, the debugger reads its commands from stdin",
+                    ))
+                }
+            },
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown argument `{other}`")),
+        }
+    }
+
+    let Some(day) = day else {
+        return Err(String::from("Missing required argument `--day`"));
+    };
+    let Some(input) = input else {
+        return Err(String::from("Missing required argument `--input`"));
+    };
+
+    Ok(Command::Debug(DebugArgs { day, part, input }))
+}
+
+fn parse_image<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut day = None;
+    let mut part = 1;
+    let mut input = Input::Stdin;
+    let mut scale = 1;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "--part" => part = parse_number(&arg, args.next())?,
+            "--input" => input = parse_input(&arg, args.next())?,
+            "--scale" => scale = parse_number(&arg, args.next())?,
+            "--output" => output = Some(parse_path(&arg, args.next())?),
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown argument `{other}`")),
+        }
+    }
+
+    let Some(day) = day else {
+        return Err(String::from("Missing required argument `--day`"));
+    };
+    if scale == 0 {
+        return Err(String::from("`--scale` must be at least 1"));
+    }
+
+    Ok(Command::Image(ImageArgs {
+        day,
+        part,
+        input,
+        scale,
+        output,
+    }))
+}
+
+fn parse_new<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut day = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown argument `{other}`")),
+        }
+    }
+
+    match day {
+        Some(day) => Ok(Command::New(NewArgs { day })),
+        None => Err(String::from("Missing required argument `--day`")),
+    }
+}
+
+fn parse_generate<I: Iterator<Item = String>>(mut args: I) -> Result<Command, String> {
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = None;
+    let mut output = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, args.next())?),
+            "--seed" => seed = parse_number(&arg, args.next())?,
+            "--size" => size = Some(parse_number(&arg, args.next())?),
+            "--output" => output = Some(parse_path(&arg, args.next())?),
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown argument `{other}`")),
+        }
+    }
+
+    let Some(day) = day else {
+        return Err(String::from("Missing required argument `--day`"));
+    };
+
+    Ok(Command::Generate(GenerateArgs {
+        day,
+        seed,
+        size,
+        output,
+    }))
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let Some(value) = value else {
+        return Err(format!("Missing value for `{flag}`"));
+    };
+
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value `{value}` for `{flag}`"))
+}
+
+fn parse_path(flag: &str, value: Option<String>) -> Result<PathBuf, String> {
+    match value {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => Err(format!("Missing value for `{flag}`")),
+    }
+}
+
+fn parse_input(flag: &str, value: Option<String>) -> Result<Input, String> {
+    match parse_path(flag, value)? {
+        path if path.as_os_str() == "-" => Ok(Input::Stdin),
+        path => Ok(Input::Path(path)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(
+            parse_args(args("run --day 6 --part 2 --input day6/input/input.txt")),
+            Ok(Command::Run(RunArgs {
+                day: 6,
+                part: Some(2),
+                input: Input::Path(PathBuf::from("day6/input/input.txt")),
+                answers: PathBuf::from(DEFAULT_ANSWERS),
+                record: false,
+                json: false,
+            }))
+        );
+        assert_eq!(
+            parse_args(args(
+                "run --day 1 --input - --answers team.txt --record --json"
+            )),
+            Ok(Command::Run(RunArgs {
+                day: 1,
+                part: None,
+                input: Input::Stdin,
+                answers: PathBuf::from("team.txt"),
+                record: true,
+                json: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_run_all() {
+        assert_eq!(
+            parse_args(args("run --all")),
+            Ok(Command::RunAll(RunAllArgs {
+                jobs: None,
+                answers: PathBuf::from(DEFAULT_ANSWERS),
+                json: false,
+            }))
+        );
+        assert_eq!(
+            parse_args(args("run --all --jobs 4 --answers team.txt --json")),
+            Ok(Command::RunAll(RunAllArgs {
+                jobs: Some(4),
+                answers: PathBuf::from("team.txt"),
+                json: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            parse_args(args("bench")),
+            Ok(Command::Bench(BenchArgs {
+                day: None,
+                runs: DEFAULT_RUNS,
+                baseline: None,
+                save: None,
+            }))
+        );
+        assert_eq!(
+            parse_args(args(
+                "bench --day 12 --runs 3 --baseline old.txt --save new.txt"
+            )),
+            Ok(Command::Bench(BenchArgs {
+                day: Some(12),
+                runs: 3,
+                baseline: Some(PathBuf::from("old.txt")),
+                save: Some(PathBuf::from("new.txt")),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_animate() {
+        assert_eq!(
+            parse_args(args("animate --day 14 --part 2 --every 50 --export frames")),
+            Ok(Command::Animate(AnimateArgs {
+                day: 14,
+                part: 2,
+                input: Input::Stdin,
+                fps: DEFAULT_FPS,
+                every: 50,
+                export: Some(PathBuf::from("frames")),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_debug() {
+        assert_eq!(
+            parse_args(args("debug --day 9 --part 2 --input day9/input/input.txt")),
+            Ok(Command::Debug(DebugArgs {
+                day: 9,
+                part: 2,
+                input: PathBuf::from("day9/input/input.txt"),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_image() {
+        assert_eq!(
+            parse_args(args("image --day 9 --part 2 --scale 4 --output rope.pgm")),
+            Ok(Command::Image(ImageArgs {
+                day: 9,
+                part: 2,
+                input: Input::Stdin,
+                scale: 4,
+                output: Some(PathBuf::from("rope.pgm")),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_new() {
+        assert_eq!(
+            parse_args(args("new --day 16")),
+            Ok(Command::New(NewArgs { day: 16 }))
+        );
+    }
+
+    #[test]
+    fn test_parse_generate() {
+        assert_eq!(
+            parse_args(args(
+                "generate --day 12 --seed 7 --size 60 --output big.txt"
+            )),
+            Ok(Command::Generate(GenerateArgs {
+                day: 12,
+                seed: 7,
+                size: Some(60),
+                output: Some(PathBuf::from("big.txt")),
+            }))
+        );
+        assert_eq!(
+            parse_args(args("generate --day 1")),
+            Ok(Command::Generate(GenerateArgs {
+                day: 1,
+                seed: 0,
+                size: None,
+                output: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_args(args("run --part 1")).is_err());
+        assert!(parse_args(args("run --day x")).is_err());
+        assert!(parse_args(args("run --day 1 --input")).is_err());
+        assert!(parse_args(args("run --day 1 --answers")).is_err());
+        assert!(parse_args(args("run --all --day 1")).is_err());
+        assert!(parse_args(args("run --all --record")).is_err());
+        assert!(parse_args(args("run --all --jobs 0")).is_err());
+        assert!(parse_args(args("run --day 1 --jobs 2")).is_err());
+        assert!(parse_args(args("bench --runs 0")).is_err());
+        assert!(parse_args(args("bench --save")).is_err());
+        assert!(parse_args(args("animate --day 9 --fps 0")).is_err());
+        assert!(parse_args(args("debug --day 10")).is_err());
+        assert!(parse_args(args("debug --day 10 --input -")).is_err());
+        assert!(parse_args(args("image --day 8 --scale 0")).is_err());
+        assert!(parse_args(args("new")).is_err());
+        assert!(parse_args(args("new --day 16 --part 1")).is_err());
+        assert!(parse_args(args("generate --seed 1")).is_err());
+        assert!(parse_args(args("generate --day 1 --seed -1")).is_err());
+        assert!(parse_args(args("walk --day 1")).is_err());
+        assert_eq!(parse_args(args("")), Ok(Command::Help));
+    }
+}
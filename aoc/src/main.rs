@@ -0,0 +1,532 @@
+mod cli;
+mod pool;
+mod scaffold;
+mod solve;
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use aoc_common::{
+    change, debug, export, input_hash, open_input_from, play, read_answers, read_baseline,
+    read_from, read_input_from, read_stdin, write_answers, write_baseline, write_image, Baseline,
+    Error, HashingReader, Json, Verdict, PHASES,
+};
+use cli::{
+    AnimateArgs, BenchArgs, Command, DebugArgs, GenerateArgs, ImageArgs, Input, NewArgs,
+    RunAllArgs, RunArgs, USAGE,
+};
+use scaffold::{scaffold, ADVENT};
+use solve::{bench_day, draw_day, generate_day, simulate_day, solve_day, stream_day, DAYS, PARTS};
+
+fn main() -> ExitCode {
+    let command = match cli::parse_args(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Help => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Command::Run(args) => run(args),
+        Command::RunAll(args) => run_all(args),
+        Command::Bench(args) => bench(args),
+        Command::Animate(args) => animate(args),
+        Command::Debug(args) => debugger(args),
+        Command::Image(args) => image(args),
+        Command::New(args) => new(args),
+        Command::Generate(args) => generate(args),
+    }
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    if !DAYS.contains(&args.day) {
+        eprintln!("Day {} is not solved yet", args.day);
+        return ExitCode::FAILURE;
+    }
+
+    let parts = match args.part {
+        Some(part) if PARTS.contains(&part) => part..=part,
+        Some(part) => {
+            eprintln!("Part {part} does not exist, expected 1 or 2");
+            return ExitCode::FAILURE;
+        }
+        None => PARTS,
+    };
+
+    let mut answers = match read_answers(&args.answers) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (name, reader): (String, Box<dyn BufRead>) = match &args.input {
+        Input::Stdin => (String::from("<stdin>"), Box::new(io::stdin().lock())),
+        Input::Path(path) => match open_input_from(path) {
+            Ok(file) => (path.display().to_string(), Box::new(file)),
+            Err(error) => {
+                eprintln!("{error}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    let mut reader = HashingReader::new(reader);
+    let solved = match solve_parts(args.day, parts, &name, &mut reader) {
+        Ok(solved) => solved,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let hash = reader.hash();
+
+    let mut status = ExitCode::SUCCESS;
+    let mut recorded = false;
+    let mut results = Vec::new();
+    for (part, elapsed, solved) in solved {
+        let solved = solved.map(|answer| {
+            let verdict = answers.check(hash, args.day, part, &answer);
+            (answer, verdict)
+        });
+        match &solved {
+            Ok((answer, Verdict::Unknown)) if args.record => {
+                answers.insert(hash, args.day, part, answer);
+                recorded = true;
+            }
+            Ok((_, Verdict::Fail(_))) | Err(_) => status = ExitCode::FAILURE,
+            Ok(_) => {}
+        }
+
+        if args.json {
+            results.push(part_json(args.day, part, Some(hash), elapsed, &solved));
+        } else {
+            match &solved {
+                Ok((answer, verdict)) => {
+                    println!("Day {} part {part}: {answer} ({verdict})", args.day)
+                }
+                Err(error) => eprintln!("{error}"),
+            }
+        }
+    }
+    if args.json {
+        println!("{}", Json::Array(results));
+    }
+
+    if recorded {
+        if let Err(error) = write_answers(&args.answers, &answers) {
+            eprintln!("{error}");
+            status = ExitCode::FAILURE;
+        }
+    }
+
+    status
+}
+
+/// Runs both parts of every day on its puzzle input, each part a job for the
+/// thread pool, then prints one table row per part.
+fn run_all(args: RunAllArgs) -> ExitCode {
+    let answers = match read_answers(&args.answers) {
+        Ok(answers) => answers,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let jobs: Vec<(u8, u8)> = DAYS
+        .iter()
+        .flat_map(|&day| PARTS.map(move |part| (day, part)))
+        .collect();
+    let threads = args
+        .jobs
+        .unwrap_or_else(pool::default_threads)
+        .min(jobs.len());
+    let start = Instant::now();
+    let ran = pool::map(threads, &jobs, |&(day, part)| {
+        // inputs are read relative to the workspace root
+        let input = match read_input_from(format!("day{day}/input/input.txt")) {
+            Ok(input) => input,
+            Err(error) => return (None, Duration::ZERO, Err(error)),
+        };
+        let hash = input_hash(&input);
+        let start = Instant::now();
+        let solved = solve_day(day, part, &input);
+        let elapsed = start.elapsed();
+        let solved = solved.map(|answer| {
+            let verdict = answers.check(hash, day, part, &answer);
+            (answer, verdict)
+        });
+        (Some(hash), elapsed, solved)
+    });
+    let wall = start.elapsed();
+
+    let failed = ran
+        .iter()
+        .filter(|(_, _, solved)| matches!(solved, Err(_) | Ok((_, Verdict::Fail(_)))))
+        .count();
+    let status = match failed {
+        0 => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    };
+
+    if args.json {
+        let results = jobs
+            .iter()
+            .zip(&ran)
+            .map(|(&(day, part), (hash, elapsed, solved))| {
+                part_json(day, part, *hash, *elapsed, solved)
+            });
+        println!("{}", Json::Array(results.collect()));
+        return status;
+    }
+
+    let cells: Vec<String> = ran
+        .iter()
+        .map(|(_, _, solved)| match solved {
+            Ok((answer, _)) => answer_cell(answer),
+            Err(_) => String::from("-"),
+        })
+        .collect();
+    let width = cells
+        .iter()
+        .map(|cell| cell.chars().count())
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{:>3}  {:>4}  {:<width$}  {:<7}  {:>10}",
+        "Day", "Part", "Answer", "Status", "Time"
+    );
+    let mut problems = Vec::new();
+    for ((&(day, part), (_, elapsed, solved)), cell) in jobs.iter().zip(&ran).zip(&cells) {
+        let verdict = match solved {
+            Ok((_, Verdict::Pass)) => "pass",
+            Ok((_, Verdict::Unknown)) => "unknown",
+            Ok((answer, Verdict::Fail(expected))) => {
+                problems.push(format!(
+                    "Day {day} part {part}: answered {answer}, expected {expected}"
+                ));
+                "FAIL"
+            }
+            Err(error) => {
+                problems.push(error.to_string());
+                "error"
+            }
+        };
+        println!(
+            "{day:>3}  {part:>4}  {cell:<width$}  {verdict:<7}  {:>10}",
+            format!("{elapsed:.1?}")
+        );
+    }
+
+    let passed = ran
+        .iter()
+        .filter(|(_, _, solved)| matches!(solved, Ok((_, Verdict::Pass))))
+        .count();
+    println!(
+        "\n{passed} passed, {failed} failed, {} unknown in {wall:.1?} on {threads} thread{}",
+        ran.len() - passed - failed,
+        if threads == 1 { "" } else { "s" }
+    );
+    for problem in problems {
+        eprintln!("{problem}");
+    }
+
+    status
+}
+
+/// An answer on one table row, multi-line answers such as day 10's screen
+/// are only counted.
+fn answer_cell(answer: &str) -> String {
+    match answer.trim_matches('\n').lines().count() {
+        0 | 1 => answer.to_string(),
+        lines => format!("[{lines} lines]"),
+    }
+}
+
+type Solved = (u8, Duration, Result<String, Error>);
+
+/// Answers `parts` of `day` with their times, up to the first that fails.
+///
+/// Days that can stream are solved in one pass over `reader` and both parts
+/// share its time, the others read all of it first and time each part.
+fn solve_parts(
+    day: u8,
+    parts: RangeInclusive<u8>,
+    name: &str,
+    reader: &mut dyn BufRead,
+) -> Result<Vec<Solved>, Error> {
+    let start = Instant::now();
+    match stream_day(day, reader) {
+        Ok(answers) => {
+            let elapsed = start.elapsed();
+            let answer = |part: u8| Ok(answers[part as usize - 1].clone());
+            return Ok(parts.map(|part| (part, elapsed, answer(part))).collect());
+        }
+        Err(Error::NoStream(_)) => {}
+        Err(error) => return Ok(vec![(*parts.start(), start.elapsed(), Err(error))]),
+    }
+
+    let contents = read_from(name, reader)?;
+    let mut solved = Vec::new();
+    for part in parts {
+        let start = Instant::now();
+        let answer = solve_day(day, part, &contents);
+        let failed = answer.is_err();
+        solved.push((part, start.elapsed(), answer));
+        if failed {
+            break;
+        }
+    }
+
+    Ok(solved)
+}
+
+fn part_json(
+    day: u8,
+    part: u8,
+    hash: Option<u64>,
+    elapsed: Duration,
+    solved: &Result<(String, Verdict), Error>,
+) -> Json {
+    let mut fields = vec![
+        ("day", Json::from(day)),
+        ("part", Json::from(part)),
+        (
+            "input_hash",
+            Json::from(hash.map(|hash| format!("{hash:016x}"))),
+        ),
+        ("elapsed_ns", Json::from(elapsed.as_nanos() as f64)),
+    ];
+    match solved {
+        Ok((answer, verdict)) => {
+            let (name, expected) = match verdict {
+                Verdict::Pass => ("pass", None),
+                Verdict::Fail(expected) => ("fail", Some(expected.as_str())),
+                Verdict::Unknown => ("unknown", None),
+            };
+            fields.push(("answer", Json::from(answer.as_str())));
+            fields.push(("verdict", Json::from(name)));
+            fields.push(("expected", Json::from(expected)));
+        }
+        Err(error) => fields.push(("error", Json::from(error.to_string()))),
+    }
+
+    Json::object(fields)
+}
+
+fn bench(args: BenchArgs) -> ExitCode {
+    let days = match args.day {
+        Some(day) if DAYS.contains(&day) => vec![day],
+        Some(day) => {
+            eprintln!("Day {day} is not solved yet");
+            return ExitCode::FAILURE;
+        }
+        None => DAYS.to_vec(),
+    };
+
+    let baseline = match args.baseline.as_ref().map(read_baseline).transpose() {
+        Ok(baseline) => baseline,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut status = ExitCode::SUCCESS;
+    let mut medians = Baseline::default();
+    println!(
+        "{:>3}  {:<5}  {:>10}  {:>10}  {:>10}  {:>8}",
+        "Day", "Phase", "Min", "Median", "Max", "Baseline"
+    );
+    for day in days {
+        // inputs are read relative to the workspace root
+        let timings = read_input_from(format!("day{day}/input/input.txt"))
+            .and_then(|input| bench_day(day, &input, args.runs));
+        let timings = match timings {
+            Ok(timings) => timings,
+            Err(error) => {
+                eprintln!("{error}");
+                status = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        for (phase, stats) in PHASES.iter().zip(timings.phases) {
+            let compared = match baseline.as_ref().and_then(|b| b.get(day, phase)) {
+                Some(old) => format!("{:+.1}%", change(old, stats.median)),
+                None => String::from("-"),
+            };
+            println!(
+                "{day:>3}  {phase:<5}  {:>10}  {:>10}  {:>10}  {compared:>8}",
+                format!("{:.1?}", stats.min),
+                format!("{:.1?}", stats.median),
+                format!("{:.1?}", stats.max),
+            );
+        }
+        medians.insert(&timings);
+    }
+
+    if let Some(path) = &args.save {
+        if let Err(error) = write_baseline(path, &medians) {
+            eprintln!("{error}");
+            status = ExitCode::FAILURE;
+        }
+    }
+
+    status
+}
+
+fn animate(args: AnimateArgs) -> ExitCode {
+    if !PARTS.contains(&args.part) {
+        eprintln!("Part {} does not exist, expected 1 or 2", args.part);
+        return ExitCode::FAILURE;
+    }
+
+    let contents = match &args.input {
+        Input::Stdin => read_stdin(),
+        Input::Path(path) => read_input_from(path),
+    };
+    let simulation = contents.and_then(|contents| simulate_day(args.day, args.part, &contents));
+    let mut simulation = match simulation {
+        Ok(simulation) => simulation,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let shown = match &args.export {
+        Some(dir) => export(simulation.as_mut(), args.every, dir)
+            .map(|count| println!("Wrote {count} frames to {}", dir.display())),
+        None => play(simulation.as_mut(), args.every, args.fps)
+            .map_err(|error| Error::Write(String::from("<stdout>"), error)),
+    };
+
+    match shown {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn debugger(args: DebugArgs) -> ExitCode {
+    if !PARTS.contains(&args.part) {
+        eprintln!("Part {} does not exist, expected 1 or 2", args.part);
+        return ExitCode::FAILURE;
+    }
+
+    let simulation = read_input_from(&args.input)
+        .and_then(|contents| simulate_day(args.day, args.part, &contents));
+    let debugged = simulation.and_then(|mut simulation| {
+        debug(simulation.as_mut(), io::stdin().lock(), io::stdout().lock())
+            .map_err(|error| Error::Write(String::from("<stdout>"), error))
+    });
+
+    match debugged {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn image(args: ImageArgs) -> ExitCode {
+    if !PARTS.contains(&args.part) {
+        eprintln!("Part {} does not exist, expected 1 or 2", args.part);
+        return ExitCode::FAILURE;
+    }
+
+    let contents = match &args.input {
+        Input::Stdin => read_stdin(),
+        Input::Path(path) => read_input_from(path),
+    };
+    let drawn = contents.and_then(|contents| draw_day(args.day, args.part, &contents));
+    let written = drawn.and_then(|image| {
+        let path = args.output.clone().unwrap_or_else(|| {
+            PathBuf::from(format!(
+                "day{}-part{}.{}",
+                args.day,
+                args.part,
+                image.extension()
+            ))
+        });
+        write_image(&path, &image, args.scale)?;
+        Ok(path)
+    });
+
+    match written {
+        Ok(path) => {
+            println!("Wrote {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn new(args: NewArgs) -> ExitCode {
+    let day = args.day;
+    if !ADVENT.contains(&day) {
+        eprintln!("Day {day} does not exist, expected 1 to 25");
+        return ExitCode::FAILURE;
+    }
+
+    // the new crate and the files listing the days are relative to the workspace root
+    match scaffold(Path::new("."), day) {
+        Ok(written) => {
+            for path in written {
+                println!("Wrote {}", path.display());
+            }
+            println!(
+                "Put the puzzle input in day{day}/input/input.txt, then once it is solved \
+                 record the answers with `aoc run --day {day} --input day{day}/input/input.txt --record`"
+            );
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn generate(args: GenerateArgs) -> ExitCode {
+    let input = match generate_day(args.day, args.seed, args.size) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(path) = &args.output else {
+        print!("{input}");
+        return ExitCode::SUCCESS;
+    };
+    match fs::write(path, input) {
+        Ok(()) => {
+            println!("Wrote {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{}", Error::Write(path.display().to_string(), error));
+            ExitCode::FAILURE
+        }
+    }
+}
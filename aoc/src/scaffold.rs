@@ -0,0 +1,251 @@
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use aoc_common::{read_input_from, Error};
+
+/// Days of an Advent of Code event.
+pub const ADVENT: RangeInclusive<u8> = 1..=25;
+
+/// Files of a new day crate, `$DAY` is replaced by the day number.
+const TEMPLATE: [(&str, &str); 5] = [
+    ("Cargo.toml", include_str!("../template/Cargo.toml.in")),
+    (".gitignore", include_str!("../template/gitignore.in")),
+    ("src/lib.rs", include_str!("../template/lib.rs.in")),
+    ("src/main.rs", include_str!("../template/main.rs.in")),
+    (
+        "input/example.txt",
+        include_str!("../template/example.txt.in"),
+    ),
+];
+
+type Edit = fn(&str, u8) -> Option<String>;
+
+/// Files that list every day, relative to the workspace root, with the edit
+/// adding a day and what that edit looks for.
+const EDITS: [(&str, Edit, &str); 3] = [
+    ("Cargo.toml", add_member, "day member"),
+    ("aoc/Cargo.toml", add_dependency, "day dependency"),
+    ("aoc/src/solve.rs", add_dispatch, "day dispatch"),
+];
+
+/// Creates the `dayN` crate in the workspace at `root` and wires it into the
+/// workspace members and the runner, returning the files written.
+///
+/// Every edit is worked out before anything is written, so a tree the edits
+/// do not apply to is left as it was.
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<PathBuf>, Error> {
+    let dir = root.join(format!("day{day}"));
+    if dir.exists() {
+        return Err(Error::Exists(dir.display().to_string()));
+    }
+
+    let mut edited = Vec::new();
+    for (file, edit, anchor) in EDITS {
+        let path = root.join(file);
+        let contents = read_input_from(&path)?;
+        let Some(contents) = edit(&contents, day) else {
+            return Err(Error::NoAnchor(path.display().to_string(), anchor));
+        };
+        edited.push((path, contents));
+    }
+
+    let mut written = Vec::new();
+    for (file, template) in TEMPLATE {
+        let path = dir.join(file);
+        write(&path, &template.replace("$DAY", &day.to_string()))?;
+        written.push(path);
+    }
+    for (path, contents) in edited {
+        write(&path, &contents)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    let failed = |error| Error::Write(path.display().to_string(), error);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(failed)?;
+    }
+
+    fs::write(path, contents).map_err(failed)
+}
+
+fn add_member(manifest: &str, day: u8) -> Option<String> {
+    insert_after_last(manifest, &format!("    \"day{day}\","), |line| {
+        line.starts_with("    \"day")
+    })
+}
+
+fn add_dependency(manifest: &str, day: u8) -> Option<String> {
+    let dependency = format!("day{day} = {{ path = \"../day{day}\" }}");
+    insert_after_last(manifest, &dependency, |line| {
+        line.starts_with("day") && line.contains("path = \"../day")
+    })
+}
+
+/// Adds the day to `solve_day`, `bench_day` and the `DAYS` list.
+fn add_dispatch(solve: &str, day: u8) -> Option<String> {
+    let solve = insert_after_last(
+        solve,
+        &format!("        {day} => solve::<day{day}::Day{day}>(input, part),"),
+        |line| line.contains(" => solve::<day"),
+    )?;
+    let solve = insert_after_last(
+        &solve,
+        &format!("        {day} => bench::<day{day}::Day{day}>(input, runs)?,"),
+        |line| line.contains(" => bench::<day"),
+    )?;
+
+    const DAYS: &str = "pub const DAYS: &[u8] = &[";
+    let start = solve.find(DAYS)?;
+    let end = start + solve[start..].find("];")? + "];".len();
+    let mut days = Vec::new();
+    for number in solve[start + DAYS.len()..end - "];".len()].split(',') {
+        match number.trim() {
+            "" => {}
+            number => days.push(number.parse::<u8>().ok()?),
+        }
+    }
+    if !days.contains(&day) {
+        days.push(day);
+        days.sort();
+    }
+
+    Some(format!(
+        "{}{}{}",
+        &solve[..start],
+        days_list(DAYS, &days),
+        &solve[end..]
+    ))
+}
+
+/// `DAYS` written out the way rustfmt lays it out: on one line when it
+/// fits, otherwise as many numbers per indented line as fit.
+fn days_list(declaration: &str, days: &[u8]) -> String {
+    const WIDTH: usize = 100;
+
+    let numbers: Vec<String> = days.iter().map(u8::to_string).collect();
+    let line = format!("{declaration}{}];", numbers.join(", "));
+    if line.len() <= WIDTH {
+        return line;
+    }
+
+    let mut text = format!("{declaration}\n");
+    let mut row = String::new();
+    for number in numbers {
+        if !row.is_empty() && 4 + row.len() + 1 + number.len() + 1 > WIDTH {
+            text += &format!("    {}\n", row.trim_end());
+            row.clear();
+        }
+        row += &format!("{number}, ");
+    }
+    text += &format!("    {}\n];", row.trim_end());
+    text
+}
+
+/// Inserts `line` after the last line matching `anchor`, `None` when no line
+/// matches. Text already containing `line` is returned unchanged.
+fn insert_after_last(text: &str, line: &str, anchor: impl Fn(&str) -> bool) -> Option<String> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    if lines.contains(&line) {
+        return Some(text.to_string());
+    }
+    let last = lines.iter().rposition(|line| anchor(line))?;
+    lines.insert(last + 1, line);
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMBERS: &str = "[workspace]\nmembers = [\n    \"aoc\",\n    \"day1\",\n]\n";
+    const DEPENDENCIES: &str = "[dependencies]\nday1 = { path = \"../day1\" }\n";
+    const SOLVE: &str = "pub const DAYS: &[u8] = &[1];
+
+pub fn solve_day(day: u8, part: u8, input: &str) -> Result<String, Error> {
+    match day {
+        1 => solve::<day1::Day1>(input, part),
+        _ => Err(Error::NoSolver { day, part }),
+    }
+}
+
+pub fn bench_day(day: u8, input: &str, runs: usize) -> Result<Timings, Error> {
+    let timings = match day {
+        1 => bench::<day1::Day1>(input, runs)?,
+        _ => return Err(Error::NoSolver { day, part: 1 }),
+    };
+";
+
+    #[test]
+    fn test_edits() {
+        assert_eq!(
+            add_member(MEMBERS, 2).unwrap(),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"day1\",\n    \"day2\",\n]\n"
+        );
+        let dependencies = add_dependency(DEPENDENCIES, 2).unwrap();
+        assert!(dependencies.ends_with("day1\" }\nday2 = { path = \"../day2\" }\n"));
+        assert_eq!(add_dependency(&dependencies, 2).unwrap(), dependencies);
+
+        let solve = add_dispatch(SOLVE, 2).unwrap();
+        assert!(solve.starts_with("pub const DAYS: &[u8] = &[1, 2];"));
+        assert!(solve.contains("        2 => solve::<day2::Day2>(input, part),\n        _ =>"));
+        assert!(solve.contains("        2 => bench::<day2::Day2>(input, runs)?,\n        _ =>"));
+
+        // skipped days are not listed
+        let solve = add_dispatch(&solve, 5).unwrap();
+        assert!(solve.starts_with("pub const DAYS: &[u8] = &[1, 2, 5];"));
+        assert_eq!(add_dispatch(&solve, 5).unwrap(), solve);
+
+        assert_eq!(add_member("[workspace]\n", 2), None);
+        assert_eq!(add_dispatch("fn main() {}\n", 2), None);
+    }
+
+    #[test]
+    fn test_days_list() {
+        let declaration = "pub const DAYS: &[u8] = &[";
+        assert_eq!(
+            days_list(declaration, &[1, 2, 3]),
+            "pub const DAYS: &[u8] = &[1, 2, 3];"
+        );
+
+        let days: Vec<u8> = (1..=25).collect();
+        let expected = "pub const DAYS: &[u8] = &[
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+];";
+        assert_eq!(days_list(declaration, &days), expected);
+
+        // a list rustfmt spread over lines is read back
+        let solve = SOLVE.replace(
+            "pub const DAYS: &[u8] = &[1];",
+            &expected.replace("24, ", ""),
+        );
+        assert!(add_dispatch(&solve, 24).unwrap().starts_with(expected));
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        write(&root.join("Cargo.toml"), MEMBERS).unwrap();
+        write(&root.join("aoc/Cargo.toml"), DEPENDENCIES).unwrap();
+
+        // nothing is created while one of the edited files is missing
+        assert!(matches!(scaffold(&root, 2), Err(Error::Open(..))));
+        assert!(!root.join("day2").exists());
+
+        write(&root.join("aoc/src/solve.rs"), SOLVE).unwrap();
+        assert_eq!(scaffold(&root, 2).unwrap().len(), 8);
+        let lib = fs::read_to_string(root.join("day2/src/lib.rs")).unwrap();
+        assert!(lib.contains("pub struct Day2;"));
+        assert!(lib.contains("const DAY: u8 = 2;"));
+        assert!(matches!(scaffold(&root, 2), Err(Error::Exists(_))));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,110 @@
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+
+use aoc_common::{bench, solve, solve_stream, Error, Image, Simulation, Timings};
+
+/// Days with a solver, in order. `aoc new` adds to it.
+pub const DAYS: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+pub const PARTS: RangeInclusive<u8> = 1..=2;
+
+/// Parses `input` with the given day's parser and returns the formatted answer.
+pub fn solve_day(day: u8, part: u8, input: &str) -> Result<String, Error> {
+    match day {
+        1 => solve::<day1::Day1>(input, part),
+        2 => solve::<day2::Day2>(input, part),
+        3 => solve::<day3::Day3>(input, part),
+        4 => solve::<day4::Day4>(input, part),
+        5 => solve::<day5::Day5>(input, part),
+        6 => solve::<day6::Day6>(input, part),
+        7 => solve::<day7::Day7>(input, part),
+        8 => solve::<day8::Day8>(input, part),
+        9 => solve::<day9::Day9>(input, part),
+        10 => solve::<day10::Day10>(input, part),
+        11 => solve::<day11::Day11>(input, part),
+        12 => solve::<day12::Day12>(input, part),
+        13 => solve::<day13::Day13>(input, part),
+        14 => solve::<day14::Day14>(input, part),
+        15 => solve::<day15::Day15>(input, part),
+        _ => Err(Error::NoSolver { day, part }),
+    }
+}
+
+/// Answers both parts in one pass over `reader`, for the days that can.
+pub fn stream_day(day: u8, reader: &mut dyn BufRead) -> Result<[String; 2], Error> {
+    match day {
+        1 => solve_stream::<day1::Day1, _>(reader),
+        6 => solve_stream::<day6::Day6, _>(reader),
+        10 => solve_stream::<day10::Day10, _>(reader),
+        _ => Err(Error::NoStream(day)),
+    }
+}
+
+/// Times the given day's parser and both parts over `runs` runs each.
+pub fn bench_day(day: u8, input: &str, runs: usize) -> Result<Timings, Error> {
+    let timings = match day {
+        1 => bench::<day1::Day1>(input, runs)?,
+        2 => bench::<day2::Day2>(input, runs)?,
+        3 => bench::<day3::Day3>(input, runs)?,
+        4 => bench::<day4::Day4>(input, runs)?,
+        5 => bench::<day5::Day5>(input, runs)?,
+        6 => bench::<day6::Day6>(input, runs)?,
+        7 => bench::<day7::Day7>(input, runs)?,
+        8 => bench::<day8::Day8>(input, runs)?,
+        9 => bench::<day9::Day9>(input, runs)?,
+        10 => bench::<day10::Day10>(input, runs)?,
+        11 => bench::<day11::Day11>(input, runs)?,
+        12 => bench::<day12::Day12>(input, runs)?,
+        13 => bench::<day13::Day13>(input, runs)?,
+        14 => bench::<day14::Day14>(input, runs)?,
+        15 => bench::<day15::Day15>(input, runs)?,
+        _ => return Err(Error::NoSolver { day, part: 1 }),
+    };
+
+    Ok(timings)
+}
+
+/// Generates an input for the given day from `seed`, `size` defaults to the
+/// scale of the puzzle input.
+pub fn generate_day(day: u8, seed: u64, size: Option<usize>) -> Result<String, Error> {
+    let (generate, default): (fn(u64, usize) -> String, usize) = match day {
+        1 => (day1::generate::generate, day1::generate::SIZE),
+        2 => (day2::generate::generate, day2::generate::SIZE),
+        3 => (day3::generate::generate, day3::generate::SIZE),
+        4 => (day4::generate::generate, day4::generate::SIZE),
+        5 => (day5::generate::generate, day5::generate::SIZE),
+        6 => (day6::generate::generate, day6::generate::SIZE),
+        7 => (day7::generate::generate, day7::generate::SIZE),
+        8 => (day8::generate::generate, day8::generate::SIZE),
+        9 => (day9::generate::generate, day9::generate::SIZE),
+        10 => (day10::generate::generate, day10::generate::SIZE),
+        11 => (day11::generate::generate, day11::generate::SIZE),
+        12 => (day12::generate::generate, day12::generate::SIZE),
+        13 => (day13::generate::generate, day13::generate::SIZE),
+        14 => (day14::generate::generate, day14::generate::SIZE),
+        15 => (day15::generate::generate, day15::generate::SIZE),
+        _ => return Err(Error::NoGenerator(day)),
+    };
+
+    Ok(generate(seed, size.unwrap_or(default)))
+}
+
+/// Parses `input` and sets up the given day's simulation for `part`.
+pub fn simulate_day(day: u8, part: u8, input: &str) -> Result<Box<dyn Simulation>, Error> {
+    match day {
+        9 => Ok(day9::simulation(&day9::parse_input(input)?, part)),
+        10 => Ok(day10::simulation(&day10::parse_input(input)?, part)),
+        14 => Ok(day14::simulation(&day14::parse_input(input)?, part)),
+        _ => Err(Error::NoSimulation(day)),
+    }
+}
+
+/// Parses `input` and draws the given day's grid as it is at the end of `part`.
+pub fn draw_day(day: u8, part: u8, input: &str) -> Result<Image, Error> {
+    match day {
+        8 => Ok(day8::image(&day8::parse_input(input)?, part)),
+        9 => Ok(day9::image(&day9::parse_input(input)?, part)),
+        12 => Ok(day12::image(&day12::parse_input(input)?, part)),
+        14 => Ok(day14::image(&day14::parse_input(input)?, part)),
+        _ => Err(Error::NoImage(day)),
+    }
+}
@@ -0,0 +1,35 @@
+use aoc_common::Rng;
+
+/// Elves in the puzzle input.
+pub const SIZE: usize = 250;
+
+/// Calorie lists of `size` elves carrying 1 to 15 snacks each.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    let elves: Vec<String> = (0..size)
+        .map(|_| {
+            let snacks = rng.range(1..=15);
+            (0..snacks)
+                .map(|_| format!("{}\n", rng.range(1000..=60000)))
+                .collect()
+        })
+        .collect();
+
+    elves.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 20), generate(3, 20));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 20)).unwrap();
+            assert!(part1(&tokens) <= part2(&tokens));
+        }
+    }
+}
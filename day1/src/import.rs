@@ -0,0 +1,226 @@
+use std::path::Path;
+
+use aoc_common::{Line, Lines, ParseError};
+
+use crate::{parse_input, Calori, Elf};
+
+/// The ways calorie lists are written down. They all parse to the same
+/// elves, so the solvers and [`crate::inventory`] work on any of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// The puzzle's own, one calorie count per line and a blank line
+    /// between elves.
+    Puzzle,
+    /// One elf per row, calorie counts separated by commas. Empty cells, as
+    /// spreadsheets write to pad short rows, and blank rows are skipped.
+    Csv,
+    /// An array holding an array of calorie counts per elf.
+    Json,
+}
+
+impl Format {
+    /// The format of a file going by its extension, the puzzle's for any
+    /// other.
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => Self::Csv,
+            Some(extension) if extension.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Puzzle,
+        }
+    }
+
+    pub fn parse(self, input: &str) -> Result<Vec<Elf>, ParseError> {
+        match self {
+            Self::Puzzle => parse_input(input),
+            Self::Csv => parse_csv(input),
+            Self::Json => parse_json(input),
+        }
+    }
+}
+
+pub fn parse_csv(input: &str) -> Result<Vec<Elf>, ParseError> {
+    let mut elfs = Vec::new();
+    for mut line in Lines::new(1, input) {
+        let mut calories = Vec::new();
+        loop {
+            line.take_while(char::is_whitespace);
+            if !line.is_empty() && !line.rest().starts_with(',') {
+                calories.push(line.unsigned("a calorie count")?);
+                line.take_while(char::is_whitespace);
+            }
+            if line.is_empty() {
+                break;
+            }
+            line.literal(",")?;
+        }
+
+        if !calories.is_empty() {
+            elfs.push(Elf::new(calories));
+        }
+    }
+
+    Ok(elfs)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Comma,
+    Calories(Calori),
+}
+
+/// Tokens of a JSON array of arrays of calorie counts, each with its line
+/// positioned on it for errors. Anything else is an error.
+fn tokenize(input: &str) -> Result<Vec<(Token, Line<'_>)>, ParseError> {
+    let mut tokens = Vec::new();
+    for mut line in Lines::new(1, input) {
+        loop {
+            line.take_while(char::is_whitespace);
+            if line.is_empty() {
+                break;
+            }
+
+            let at = line;
+            let token = if line.eat("[") {
+                Token::Open
+            } else if line.eat("]") {
+                Token::Close
+            } else if line.eat(",") {
+                Token::Comma
+            } else {
+                Token::Calories(line.unsigned("`[`, `]`, `,` or a calorie count")?)
+            };
+            tokens.push((token, at));
+        }
+    }
+
+    Ok(tokens)
+}
+
+pub fn parse_json(input: &str) -> Result<Vec<Elf>, ParseError> {
+    let lines = Lines::new(1, input);
+    let mut tokens = tokenize(input)?.into_iter();
+    // the next token when `wanted` holds for it, an error pointing at it
+    // otherwise
+    let mut next = |expected: &str, wanted: fn(Token) -> bool| match tokens.next() {
+        Some((token, _)) if wanted(token) => Ok(token),
+        Some((_, line)) => Err(line.error(expected)),
+        None => Err(lines.eof(expected)),
+    };
+    let open = |token| token == Token::Open;
+    let close = |token| matches!(token, Token::Comma | Token::Close);
+
+    // only the outer array may be empty
+    next("`[`", open)?;
+    let mut elfs = Vec::new();
+    let mut token = next("`[` or `]`", |token| {
+        matches!(token, Token::Open | Token::Close)
+    })?;
+    while token == Token::Open {
+        let mut calories = Vec::new();
+        loop {
+            if let Token::Calories(count) = next("a calorie count", |token| {
+                matches!(token, Token::Calories(_))
+            })? {
+                calories.push(count);
+            }
+            if next("`,` or `]`", close)? == Token::Close {
+                break;
+            }
+        }
+        elfs.push(Elf::new(calories));
+
+        token = next("`,` or `]`", close)?;
+        if token == Token::Comma {
+            token = next("`[`", open)?;
+        }
+    }
+
+    match tokens.next() {
+        Some((_, line)) => Err(line.error("the end of the input")),
+        None => Ok(elfs),
+    }
+}
+
+/// The elves in the puzzle's format, which [`parse_input`] reads back.
+pub fn to_puzzle(elfs: &[Elf]) -> String {
+    elfs.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::Inventory;
+    use crate::{part1, part2};
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+    const CSV: &str = "1000,2000,3000\n4000\n5000,6000\n7000,8000,9000\n10000\n";
+    const JSON: &str = "[\n  [1000, 2000, 3000],\n  [4000],\n  [5000, 6000],\n  \
+                        [7000, 8000, 9000],\n  [10000]\n]\n";
+
+    #[test]
+    fn test_formats() {
+        let elfs = parse_input(EXAMPLE).unwrap();
+        for (format, input) in [(Format::Csv, CSV), (Format::Json, JSON)] {
+            let imported = format.parse(input).unwrap();
+            assert_eq!(imported, elfs);
+            assert_eq!((part1(&imported), part2(&imported)), (24000, 45000));
+            assert_eq!(Inventory::new(&imported).median(), Some(10000));
+        }
+
+        let csv = "1000, 2000,,\r\n,,\r\n\r\n 3000 ,\r\n";
+        let json = "[[1000,2000],[3000]]";
+        assert_eq!(parse_csv(csv).unwrap(), parse_json(json).unwrap());
+        assert_eq!(parse_json(" [ ] ").unwrap(), []);
+        assert_eq!(parse_csv("").unwrap(), []);
+    }
+
+    #[test]
+    fn test_format_of() {
+        assert_eq!(Format::of(Path::new("team/calories.CSV")), Format::Csv);
+        assert_eq!(Format::of(Path::new("calories.json")), Format::Json);
+        assert_eq!(Format::of(Path::new("input/input.txt")), Format::Puzzle);
+        assert_eq!(Format::of(Path::new("input")), Format::Puzzle);
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |line, column, text, expected| {
+            Err::<Vec<Elf>, _>(ParseError::new(1, line, column, text, expected))
+        };
+        assert_eq!(parse_csv("1000,2000\n3000;4000\n"), error(2, 5, ";", "`,`"));
+        assert_eq!(parse_csv("1000,-5\n"), error(1, 6, "-", "a calorie count"));
+        assert_eq!(
+            parse_json("[\n  [1000],\n  [2000, x]\n]"),
+            error(3, 10, "x", "`[`, `]`, `,` or a calorie count")
+        );
+        assert_eq!(
+            parse_json("[[1000], []]"),
+            error(1, 11, "]", "a calorie count")
+        );
+        assert_eq!(
+            parse_json("[[1000] [2000]]"),
+            error(1, 9, "[", "`,` or `]`")
+        );
+        assert_eq!(parse_json("[[1000]"), error(2, 1, "", "`,` or `]`"));
+        assert_eq!(
+            parse_json("[] []"),
+            error(1, 4, "[", "the end of the input")
+        );
+        assert_eq!(parse_json(""), error(1, 1, "", "`[`"));
+    }
+
+    #[test]
+    fn test_to_puzzle() {
+        let elfs = parse_json(JSON).unwrap();
+        assert_eq!(to_puzzle(&elfs), EXAMPLE.trim_end().to_string() + "\n");
+        for seed in 0..20 {
+            let elfs = parse_input(&crate::generate::generate(seed, 10)).unwrap();
+            assert_eq!(parse_input(&to_puzzle(&elfs)).unwrap(), elfs);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use crate::{total, Calori, Elf};
+
+/// Questions about the elves' calorie totals, all asked of the same parsed
+/// elves. Elves are numbered by their 0-based index in the input.
+///
+/// Elves carrying the same total share a rank, the one of the first of them
+/// (1, 2, 2, 4), and are listed in input order.
+pub struct Inventory<'a> {
+    elfs: &'a [Elf],
+}
+
+/// One elf's line of [`Inventory::report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfReport {
+    pub index: usize,
+    pub snacks: usize,
+    pub total: Calori,
+    pub rank: usize,
+}
+
+impl fmt::Display for ElfReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Elf {}: {} snacks, {} calories, rank {}",
+            self.index + 1,
+            self.snacks,
+            self.total,
+            self.rank
+        )
+    }
+}
+
+impl<'a> Inventory<'a> {
+    pub fn new(elfs: &'a [Elf]) -> Self {
+        Self { elfs }
+    }
+
+    /// Index and total of the `k` elves carrying the most, largest first.
+    ///
+    /// Keeps a heap of at most `k` elves while going through them once, so
+    /// it takes O(n log k) rather than sorting all of them.
+    pub fn top(&self, k: usize) -> Vec<(usize, Calori)> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        // the heap's top is the elf to drop first: the smallest total, and
+        // the later elf among equal totals
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for (index, elf) in self.elfs.iter().enumerate() {
+            heap.push(Reverse((elf.sum, Reverse(index))));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| (index, total))
+            .collect()
+    }
+
+    /// Calories carried by the `k` elves carrying the most.
+    pub fn top_total(&self, k: usize) -> Calori {
+        total(self.top(k).iter().map(|(_, total)| total))
+    }
+
+    /// Rank of elf `index`, 1 for the elves carrying the most, `None` when
+    /// there is no such elf.
+    pub fn rank(&self, index: usize) -> Option<usize> {
+        let total = self.elfs.get(index)?.sum;
+        Some(self.elfs.iter().filter(|elf| elf.sum > total).count() + 1)
+    }
+
+    pub fn min(&self) -> Option<Calori> {
+        self.elfs.iter().map(|elf| elf.sum).min()
+    }
+
+    pub fn max(&self) -> Option<Calori> {
+        self.elfs.iter().map(|elf| elf.sum).max()
+    }
+
+    /// The total at or below which `percent` percent of the elves are, by
+    /// the nearest-rank method: the smallest total counted in at least that
+    /// share of elves. `None` without elves or above 100 percent.
+    pub fn percentile(&self, percent: u8) -> Option<Calori> {
+        if self.elfs.is_empty() || percent > 100 {
+            return None;
+        }
+
+        let rank = (percent as usize * self.elfs.len()).div_ceil(100).max(1);
+        let mut totals: Vec<Calori> = self.elfs.iter().map(|elf| elf.sum).collect();
+        let (_, total, _) = totals.select_nth_unstable(rank - 1);
+        Some(*total)
+    }
+
+    /// The 50th percentile, the lower of the middle two for an even number
+    /// of elves.
+    pub fn median(&self) -> Option<Calori> {
+        self.percentile(50)
+    }
+
+    /// Every elf's snacks, total and rank, in input order.
+    pub fn report(&self) -> Vec<ElfReport> {
+        let mut order: Vec<usize> = (0..self.elfs.len()).collect();
+        order.sort_by_key(|index| Reverse(self.elfs[*index].sum));
+
+        let mut ranks = vec![0; self.elfs.len()];
+        for (position, index) in order.iter().enumerate() {
+            ranks[*index] = match position {
+                0 => 1,
+                _ if self.elfs[order[position - 1]].sum == self.elfs[*index].sum => {
+                    ranks[order[position - 1]]
+                }
+                _ => position + 1,
+            };
+        }
+
+        self.elfs
+            .iter()
+            .zip(ranks)
+            .enumerate()
+            .map(|(index, (elf, rank))| ElfReport {
+                index,
+                snacks: elf.len,
+                total: elf.sum,
+                rank,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_top() {
+        let elfs = parse_input(EXAMPLE).unwrap();
+        let inventory = Inventory::new(&elfs);
+        assert_eq!(inventory.top(2), [(3, 24000), (2, 11000)]);
+        assert_eq!(inventory.top_total(3), 45000);
+        assert_eq!(inventory.top(0), []);
+        assert_eq!(inventory.top(9).len(), 5);
+    }
+
+    #[test]
+    fn test_ties() {
+        let elfs = parse_input("5\n\n9\n\n5\n\n9\n\n1\n\n").unwrap();
+        let inventory = Inventory::new(&elfs);
+        assert_eq!(inventory.top(3), [(1, 9), (3, 9), (0, 5)]);
+        let ranks: Vec<Option<usize>> = (0..6).map(|index| inventory.rank(index)).collect();
+        assert_eq!(ranks, [Some(3), Some(1), Some(3), Some(1), Some(5), None]);
+        let reported: Vec<usize> = inventory.report().iter().map(|r| r.rank).collect();
+        assert_eq!(reported, [3, 1, 3, 1, 5]);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let elfs = parse_input(EXAMPLE).unwrap();
+        let inventory = Inventory::new(&elfs);
+        assert_eq!(
+            (inventory.min(), inventory.max()),
+            (Some(4000), Some(24000))
+        );
+        assert_eq!(inventory.median(), Some(10000));
+        assert_eq!(inventory.percentile(0), Some(4000));
+        assert_eq!(inventory.percentile(20), Some(4000));
+        assert_eq!(inventory.percentile(21), Some(6000));
+        assert_eq!(inventory.percentile(100), Some(24000));
+        assert_eq!(inventory.percentile(101), None);
+        assert_eq!(Inventory::new(&[]).median(), None);
+    }
+
+    #[test]
+    fn test_report() {
+        let elfs = parse_input(EXAMPLE).unwrap();
+        let report = Inventory::new(&elfs).report();
+        assert_eq!(
+            report[2],
+            ElfReport {
+                index: 2,
+                snacks: 2,
+                total: 11000,
+                rank: 2,
+            }
+        );
+        assert_eq!(
+            report[0].to_string(),
+            "Elf 1: 3 snacks, 6000 calories, rank 4"
+        );
+    }
+
+    /// Top-K against sorting every total.
+    #[test]
+    fn test_top_reference() {
+        for seed in 0..20 {
+            let elfs = parse_input(&crate::generate::generate(seed, 60)).unwrap();
+            let mut sorted: Vec<(usize, Calori)> =
+                elfs.iter().map(|elf| elf.sum).enumerate().collect();
+            sorted.sort_by_key(|(index, total)| (Reverse(*total), *index));
+            for k in [1, 3, 10, 60] {
+                assert_eq!(Inventory::new(&elfs).top(k), sorted[..k]);
+            }
+        }
+    }
+}
@@ -1,30 +1,14 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::str::FromStr;
+pub mod generate;
+pub mod import;
+pub mod inventory;
 
-type Calori = i32;
+use std::fmt;
+use std::io::BufRead;
 
-#[derive(Debug, PartialEq)]
-enum LineParser {
-    ELF(Calori),
-    EMPTY,
-}
+use aoc_common::{stream_lines, Line, Lines, ParseError, Solution, Streaming};
+use inventory::Inventory;
 
-impl FromStr for LineParser {
-    type Err = ();
-
-    fn from_str(input: &str) -> Result<LineParser, Self::Err> {
-        if input == "" {
-            return Ok(LineParser::EMPTY);
-        } else {
-            let calori = input.parse();
-            match calori {
-                Ok(c) => Ok(LineParser::ELF(c)),
-                Err(_) => Err(()),
-            }
-        }
-    }
-}
+pub type Calori = i32;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Elf {
@@ -33,39 +17,83 @@ pub struct Elf {
     sum: i32,
 }
 
-pub fn parse_input(mut file: File) -> Vec<Elf> {
-    let mut contents = String::with_capacity(10000);
-    file.read_to_string(&mut contents).unwrap();
-
-    let mut elfs: Vec<Elf> = Vec::new();
-    let mut calories: Vec<i32> = Vec::new();
-    for l in contents.lines() {
-        let parsed_line = LineParser::from_str(l);
-        match parsed_line {
-            Ok(line) => match line {
-                LineParser::EMPTY => {
-                    elfs.push(Elf {
-                        calories: calories.clone(),
-                        len: calories.len(),
-                        sum: calories.iter().sum(),
-                    });
-                    calories.clear();
-                }
-                LineParser::ELF(calori) => calories.push(calori),
-            },
-            Err(_) => panic!("WARNING: Line was not parsed"),
+impl Elf {
+    pub fn new(calories: Vec<Calori>) -> Self {
+        Self {
+            len: calories.len(),
+            sum: total(&calories),
+            calories,
+        }
+    }
+
+    /// Calories of each snack, in input order.
+    pub fn calories(&self) -> &[Calori] {
+        &self.calories
+    }
+
+    /// Number of snacks.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Calories of all snacks together.
+    pub fn sum(&self) -> Calori {
+        self.sum
+    }
+}
+
+/// One calorie count per line.
+impl fmt::Display for Elf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for calories in &self.calories {
+            writeln!(f, "{calories}")?;
         }
+        Ok(())
     }
+}
 
-    elfs
+/// Calories added up. An overflow panics as `+` does with overflow checks
+/// on, in every build, and is reported where this is called rather than
+/// inside `Iterator::sum`.
+#[track_caller]
+pub(crate) fn total<'a>(calories: impl IntoIterator<Item = &'a Calori>) -> Calori {
+    calories
+        .into_iter()
+        .try_fold(0, |total: Calori, calories| total.checked_add(*calories))
+        .expect("attempt to add with overflow")
 }
 
-pub fn get_answer(elfs: &Vec<Elf>) -> i32 {
-    let mut tmp: Vec<Elf> = elfs.clone();
+/// A calorie count, with any whitespace around it, a stray `\r` included.
+fn calorie(line: &mut Line<'_>) -> Result<Calori, ParseError> {
+    line.take_while(char::is_whitespace);
+    let calories = line.unsigned("a calorie count or an empty line")?;
+    line.take_while(char::is_whitespace);
+    Ok(calories)
+}
 
-    tmp.sort_by_key(|e| e.sum);
+fn is_blank(line: &Line<'_>) -> bool {
+    line.rest().trim().is_empty()
+}
 
-    tmp.iter().rev().take(3).map(|e| e.sum).sum()
+/// Elves are separated by one or more blank lines, whitespace-only ones
+/// included. The last one needs no blank line after it.
+pub fn parse_input(input: &str) -> Result<Vec<Elf>, ParseError> {
+    Lines::new(1, input)
+        .blocks()
+        .map(|block| block.parse_each(calorie).map(Elf::new))
+        .collect()
+}
+
+pub fn part1(elfs: &[Elf]) -> i32 {
+    elfs.iter().map(|e| e.sum).max().unwrap_or(0)
+}
+
+pub fn part2(elfs: &[Elf]) -> i32 {
+    Inventory::new(elfs).top_total(3)
 }
 
 #[derive(Debug, PartialEq)]
@@ -75,4 +103,164 @@ pub struct Position {
     pub aim: i32,
 }
 
-pub const CORRECT_ANSWER: i32 = 201524;
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Elf>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+impl Streaming for Day1 {
+    /// Keeps only the three largest totals while reading.
+    fn stream<R: BufRead>(reader: R) -> aoc_common::Result<(i32, i32)> {
+        let mut top: [Calori; 3] = [0; 3];
+        let mut sum = 0;
+        let mut push = |sum: &mut Calori| {
+            if let Some(i) = top.iter().position(|total| *sum > *total) {
+                top[i..].rotate_right(1);
+                top[i] = *sum;
+            }
+            *sum = 0;
+        };
+
+        stream_lines(1, reader, |line| {
+            if is_blank(&line) {
+                push(&mut sum);
+            } else {
+                sum += line.all(calorie)?;
+            }
+            Ok(())
+        })?;
+        // the last elf may have no blank line after it
+        push(&mut sum);
+
+        Ok((top[0], total(&top)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("1000\n2000\n\n3x00\n"),
+            Err(ParseError::new(
+                1,
+                4,
+                1,
+                "3x00",
+                "a calorie count or an empty line"
+            ))
+        );
+        assert_eq!(
+            parse_input("1000\r\n\r\n  12 kcal\r\n"),
+            Err(ParseError::new(1, 3, 6, "kcal", "the end of the line"))
+        );
+    }
+
+    #[test]
+    fn test_line_endings() {
+        let expected = parse_input(EXAMPLE).unwrap();
+        for input in [
+            EXAMPLE.trim_end().to_string(),
+            EXAMPLE.replace('\n', "\r\n"),
+            EXAMPLE.trim_end().replace('\n', "\r\n"),
+            format!("\n\n{}\n \n\t\n", EXAMPLE.replace("\n\n", "\n  \n\n")),
+            EXAMPLE.replace('\n', " \n\t"),
+        ] {
+            assert_eq!(parse_input(&input).unwrap(), expected, "{input:?}");
+            assert_eq!(Day1::stream(input.as_bytes()).unwrap(), (24000, 45000));
+        }
+        assert_eq!(parse_input("").unwrap(), []);
+    }
+
+    #[test]
+    fn test_last_elf() {
+        let elfs = parse_input("1\n\n2\n3").unwrap();
+        assert_eq!(elfs.iter().map(Elf::sum).collect::<Vec<_>>(), [1, 5]);
+        assert_eq!(Day1::stream("9\n\n1\n\n70".as_bytes()).unwrap(), (70, 80));
+    }
+
+    #[test]
+    fn test_stream() {
+        assert_eq!(Day1::stream(EXAMPLE.as_bytes()).unwrap(), (24000, 45000));
+        assert!(matches!(
+            Day1::stream("1000\n\n3x00\n".as_bytes()),
+            Err(aoc_common::Error::Parse(error)) if error.line == 3
+        ));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let in_day1 = |error| {
+            matches!(error, Some(aoc_common::Error::Overflow { operation, .. })
+                if operation.starts_with("attempt to add with overflow at day1/src/"))
+        };
+        // one elf's snacks, then the top three elves
+        assert!(in_day1(
+            aoc_common::solve::<Day1>("2147483647\n1\n", 1).err()
+        ));
+        let top = "2147483647\n\n1\n";
+        assert!(in_day1(aoc_common::solve::<Day1>(top, 2).err()));
+        assert!(in_day1(
+            aoc_common::solve_stream::<Day1, _>(top.as_bytes()).err()
+        ));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 24000);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 45000);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day1>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let elfs = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text: String = elfs.iter().map(|elf| format!("{elf}\n")).collect();
+            assert_eq!(parse_input(&text).unwrap(), elfs);
+        }
+    }
+
+    #[test]
+    fn test_reference() {
+        for seed in 0..50 {
+            let input = generate::generate(seed, 10);
+            let elfs = parse_input(&input).unwrap();
+            let mut sums: Vec<i32> = elfs.iter().map(|elf| elf.calories.iter().sum()).collect();
+            sums.sort_by(|a, b| b.cmp(a));
+            let expected = (sums[0], sums.iter().take(3).sum());
+
+            assert_eq!((part1(&elfs), part2(&elfs)), expected);
+            assert_eq!(Day1::stream(input.as_bytes()).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use aoc_common::Rng;
+
+/// Instructions in the puzzle input.
+pub const SIZE: usize = 140;
+
+/// A program of `size` instructions, two thirds of them `addx`, keeping the
+/// register within the screen's width.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut register = 1;
+
+    (0..size)
+        .map(|_| {
+            if rng.chance(1, 3) {
+                return String::from("noop\n");
+            }
+            let value = rng.range(-register.min(20)..=(39 - register).min(20));
+            register += value;
+            format!("addx {value}\n")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2, Token};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 100), generate(3, 100));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 100)).unwrap();
+            assert_eq!(tokens.len(), 100);
+            let mut register = 1;
+            for token in &tokens {
+                if let Token::Addx(_, value) = token {
+                    register += value;
+                }
+                assert!((0..40).contains(&register));
+            }
+            part1(&tokens);
+            assert_eq!(part2(&tokens).lines().count(), 7);
+        }
+    }
+}
@@ -1,118 +1,375 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::str::FromStr;
+pub mod generate;
 
-#[derive(Debug)]
+use std::fmt;
+use std::io::BufRead;
+
+use aoc_common::{stream_lines, Line, Lines, ParseError, Simulation, Solution, Streaming};
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     Noop(i32),
     Addx(i32, i32),
 }
 
-impl FromStr for Token {
-    type Err = ();
-
-    fn from_str(input: &str) -> Result<Token, ()> {
-        let mut split = input.split_whitespace();
-        let first = split.next().unwrap();
-        let second = split.next();
-        match first {
-            "noop" => Ok(Token::Noop(1)),
-            "addx" => Ok(Token::Addx(2, second.unwrap().parse().unwrap())),
-            _ => unreachable!("Pick cant be parsed!"),
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Noop(_) => write!(f, "noop"),
+            Token::Addx(_, value) => write!(f, "addx {value}"),
         }
     }
 }
 
-type Tokens = Vec<Token>;
+fn parse_instruction(line: &mut Line) -> Result<Token, ParseError> {
+    if line.eat("noop") {
+        return Ok(Token::Noop(1));
+    }
+    if line.eat("addx") {
+        line.literal(" ")?;
+        return Ok(Token::Addx(2, line.signed("a value")?));
+    }
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+    Err(line.error("noop or addx"))
+}
+
+type Tokens = Vec<Token>;
 
-    let mut contents = String::with_capacity(10000);
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let tokens = Lines::new(10, input).parse_each(parse_instruction)?;
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
+    if tokens.is_empty() {
+        return Err(ParseError::eof(10, input, "an instruction"));
     }
+
+    Ok(tokens)
 }
 
-pub fn parse_input(input: &String) -> Tokens {
-    let mut tokens = Tokens::new();
+pub fn part1(tokens: &Tokens) -> i32 {
+    run(tokens).signal_strength
+}
 
-    for line in input.lines() {
-        tokens.push(Token::from_str(line).unwrap());
+pub fn part2(tokens: &Tokens) -> String {
+    screen(&run(tokens).pixels)
+}
+
+fn run(tokens: &Tokens) -> Cpu {
+    let mut cpu = Cpu::new();
+    for token in tokens {
+        cpu.execute(token);
     }
 
-    tokens
+    cpu
 }
 
-pub fn get_answer(tokens: &Tokens) -> String {
-    let mut reg_value: i32 = 1;
+/// The CRT drawing one pixel per cycle, only part 2 draws anything.
+pub fn simulation(tokens: &Tokens, _part: u8) -> Box<dyn Simulation> {
+    Box::new(Crt::new(tokens))
+}
 
-    let mut iter = tokens.iter();
+const WIDTH: usize = 40;
+const HEIGHT: usize = 6;
 
-    let (mut wait_cycles, mut wait_token) = {
-        let token = iter.next().unwrap();
-        match token {
-            Token::Noop(cycles) => (*cycles, Token::Noop(*cycles)),
-            Token::Addx(cycles, value) => (*cycles, Token::Addx(*cycles, *value)),
-        }
-    };
+/// Works out both parts while the program runs, one cycle at a time.
+struct Cpu {
+    reg_value: i32,
+    cycle: usize,
+    /// Cycles the current instruction has run for.
+    busy: i32,
+    signal_strength: i32,
+    pixels: [char; WIDTH * HEIGHT],
+}
 
-    let mut sprite_location;
-    let mut crt = ['.'; 240];
+impl Cpu {
+    fn new() -> Self {
+        Self {
+            reg_value: 1,
+            cycle: 0,
+            busy: 0,
+            signal_strength: 0,
+            pixels: ['.'; WIDTH * HEIGHT],
+        }
+    }
 
-    for cycle in 0..240 {
-        if [40, 80, 120, 160, 200, 240].contains(&cycle) {
-            reg_value += 40;
+    /// Runs one cycle of `token`, true when that finished it.
+    fn tick(&mut self, token: &Token) -> bool {
+        let pixel = self.cycle;
+        if pixel < self.pixels.len() && ((pixel % WIDTH) as i32 - self.reg_value).abs() <= 1 {
+            self.pixels[pixel] = '#';
         }
 
-        sprite_location = [reg_value - 1, reg_value, reg_value + 1];
+        self.cycle += 1;
+        if self.cycle <= 220 && self.cycle % 40 == 20 {
+            self.signal_strength += self.cycle as i32 * self.reg_value;
+        }
 
-        if sprite_location.contains(&cycle) {
-            crt[cycle as usize] = '#';
+        let (cycles, value) = match token {
+            Token::Noop(cycles) => (*cycles, 0),
+            Token::Addx(cycles, value) => (*cycles, *value),
+        };
+        self.busy += 1;
+        if self.busy < cycles {
+            return false;
         }
+        self.busy = 0;
+        self.reg_value += value;
+        true
+    }
 
-        wait_cycles -= 1;
-        if wait_cycles != 0 {
-            continue;
+    fn execute(&mut self, token: &Token) {
+        while !self.tick(token) {}
+    }
+}
+
+fn screen(pixels: &[char]) -> String {
+    let mut str = String::new();
+    for row in pixels.chunks(WIDTH) {
+        str.push('\n');
+        str.extend(row);
+    }
+
+    str
+}
+
+/// The program run on a [`Cpu`] one cycle per step, until the screen is
+/// drawn or the program ends.
+struct Crt {
+    program: Tokens,
+    next: usize,
+    cpu: Cpu,
+}
+
+impl Crt {
+    fn new(tokens: &Tokens) -> Self {
+        Self {
+            program: tokens.clone(),
+            next: 0,
+            cpu: Cpu::new(),
         }
+    }
+}
 
-        match wait_token {
-            Token::Noop(_cycles) => {}
-            Token::Addx(_cycles, value) => reg_value += value,
+impl Simulation for Crt {
+    fn step(&mut self) -> bool {
+        let Some(token) = self.program.get(self.next) else {
+            return false;
         };
+        if self.cpu.cycle == self.cpu.pixels.len() {
+            return false;
+        }
 
-        (wait_cycles, wait_token) = {
-            let token = iter.next();
-            match token {
-                Some(Token::Noop(cycles)) => (*cycles, Token::Noop(*cycles)),
-                Some(Token::Addx(cycles, value)) => (*cycles, Token::Addx(*cycles, *value)),
-                None => {
-                    break;
-                }
-            }
-        };
+        if self.cpu.tick(token) {
+            self.next += 1;
+        }
+        true
     }
 
-    let mut str = String::new();
-    for (pos, ch) in crt.iter().enumerate() {
-        if pos % 40 == 0 {
-            str.push('\n');
+    /// The pixels drawn so far with the beam as `@`, and the sprite below.
+    fn frame(&self) -> String {
+        let cpu = &self.cpu;
+        let mut frame = String::new();
+        for (i, pixel) in cpu.pixels.iter().enumerate() {
+            frame.push(match i {
+                i if i == cpu.cycle => '@',
+                i if i > cpu.cycle => ' ',
+                _ => *pixel,
+            });
+            if i % WIDTH == WIDTH - 1 {
+                frame.push('\n');
+            }
+        }
+        frame.push('\n');
+        for column in 0..WIDTH as i32 {
+            frame.push(if (column - cpu.reg_value).abs() <= 1 {
+                '#'
+            } else {
+                ' '
+            });
         }
-        str.push(*ch);
+        frame.push('\n');
+
+        frame
+    }
+
+    /// `instruction` counts from 1, like the input's lines.
+    fn variables(&self) -> Vec<(&'static str, i64)> {
+        vec![
+            ("cycle", self.cpu.cycle as i64),
+            ("x", self.cpu.reg_value as i64),
+            ("instruction", self.next as i64 + 1),
+        ]
     }
+}
 
-    println!("{str}");
+pub struct Day10;
 
-    str
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Tokens;
+    type Output1 = i32;
+    type Output2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
 
-pub const CORRECT_ANSWER: &str = "
-###...##..###....##..##..###..#..#.###..
-#..#.#..#.#..#....#.#..#.#..#.#..#.#..#.
-#..#.#..#.#..#....#.#....###..####.#..#.
-###..####.###.....#.#....#..#.#..#.###..
-#....#..#.#....#..#.#..#.#..#.#..#.#....
-#....#..#.#.....##...##..###..#..#.#....";
+impl Streaming for Day10 {
+    fn stream<R: BufRead>(reader: R) -> aoc_common::Result<(i32, String)> {
+        let mut cpu = Cpu::new();
+        let lines = stream_lines(10, reader, |line| {
+            cpu.execute(&line.all(parse_instruction)?);
+            Ok(())
+        })?;
+
+        if lines == 0 {
+            return Err(ParseError::new(10, 1, 1, "", "an instruction").into());
+        }
+
+        Ok((cpu.signal_strength, screen(&cpu.pixels)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("noop\naddx 3\naddx x\n"),
+            Err(ParseError::new(10, 3, 6, "x", "a value"))
+        );
+        assert_eq!(
+            parse_input("noop\nsubx 3\n"),
+            Err(ParseError::new(10, 2, 1, "subx", "noop or addx"))
+        );
+        assert_eq!(
+            parse_input(""),
+            Err(ParseError::new(10, 1, 1, "", "an instruction"))
+        );
+    }
+
+    #[test]
+    fn test_stream() {
+        let tokens = parse_input(EXAMPLE).unwrap();
+        assert_eq!(
+            Day10::stream(EXAMPLE.as_bytes()).unwrap(),
+            (part1(&tokens), part2(&tokens))
+        );
+        assert!(matches!(
+            Day10::stream("".as_bytes()),
+            Err(aoc_common::Error::Parse(error)) if error.expected == "an instruction"
+        ));
+    }
+
+    #[test]
+    fn test_debug() {
+        use aoc_common::{DebugCommand, Debugger};
+
+        let mut crt = simulation(&parse_input(EXAMPLE).unwrap(), 2);
+        let mut debugger = Debugger::new(crt.as_mut());
+        // during the 20th cycle
+        debugger.execute(&DebugCommand::Step(19)).unwrap();
+        assert!(debugger.variables().contains(&("x", 21)));
+        let until = DebugCommand::parse("until cycle >= 219").unwrap();
+        debugger.execute(&until).unwrap();
+        assert!(debugger.variables().contains(&("x", 18)));
+    }
+
+    #[test]
+    fn test_simulation() {
+        let tokens = parse_input(EXAMPLE).unwrap();
+        let mut crt = simulation(&tokens, 2);
+        while crt.step() {}
+        let drawn: String = crt
+            .frame()
+            .lines()
+            .take(HEIGHT)
+            .map(|row| format!("\n{row}"))
+            .collect();
+        assert_eq!(drawn, part2(&tokens));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 13140);
+    }
+
+    #[test]
+    fn test_part2() {
+        let expected = "
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....";
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), expected);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day10>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 50)).unwrap();
+            let text: String = tokens.iter().map(|token| format!("{token}\n")).collect();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Lists the register's value during every cycle, then reads both answers
+    /// off the list.
+    #[test]
+    fn test_reference() {
+        for seed in 0..20 {
+            let input = generate::generate(seed, generate::SIZE);
+            let tokens = parse_input(&input).unwrap();
+
+            let mut during = vec![];
+            let mut x = 1;
+            for token in &tokens {
+                match token {
+                    Token::Noop(_) => during.push(x),
+                    Token::Addx(_, value) => {
+                        during.extend([x, x]);
+                        x += value;
+                    }
+                }
+            }
+
+            let strength: i32 = [20, 60, 100, 140, 180, 220]
+                .iter()
+                .filter(|cycle| **cycle <= during.len())
+                .map(|cycle| *cycle as i32 * during[cycle - 1])
+                .sum();
+            let mut crt = String::new();
+            for row in 0..6 {
+                crt.push('\n');
+                for column in 0..40 {
+                    let lit = during
+                        .get(row * 40 + column)
+                        .is_some_and(|x| (x - column as i32).abs() <= 1);
+                    crt.push(if lit { '#' } else { '.' });
+                }
+            }
+
+            assert_eq!((part1(&tokens), part2(&tokens)), (strength, crt.clone()));
+            assert_eq!(Day10::stream(input.as_bytes()).unwrap(), (strength, crt));
+        }
+    }
+}
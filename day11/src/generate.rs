@@ -0,0 +1,95 @@
+use aoc_common::Rng;
+
+use crate::{parse_input, simulate, Item, Monkey, Relief};
+
+/// Monkeys in the puzzle input.
+pub const SIZE: usize = 8;
+
+/// The distinct prime divisors the monkeys test with.
+const PRIMES: [i64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+/// Notes on `size` monkeys, clamped to 2 to 9 so each tests a different
+/// prime. Like the puzzle, one monkey squares the worry level when that
+/// keeps part 1 within an [`Item`].
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let size = size.clamp(2, PRIMES.len());
+
+    // worry levels easily overflow in part 1, which has no modulus, so
+    // redraw until they do not and stop squaring when that keeps failing
+    for attempt in 0.. {
+        let notes = notes(&mut rng, size, attempt < 16);
+        if fits(&parse_input(&notes).expect("generated notes parse")) {
+            return notes;
+        }
+    }
+    unreachable!()
+}
+
+fn notes(rng: &mut Rng, size: usize, square: bool) -> String {
+    let mut primes = PRIMES;
+    rng.shuffle(&mut primes);
+    let squaring = square.then(|| rng.index(size));
+
+    let mut notes = Vec::new();
+    for (monkey, divisor) in primes.iter().take(size).enumerate() {
+        let items: Vec<String> = (0..rng.range(1..=6))
+            .map(|_| rng.range(50..=99).to_string())
+            .collect();
+        let operation = if squaring == Some(monkey) {
+            String::from("* old")
+        } else if rng.chance(1, 3) {
+            format!("* {}", rng.range(2..=19))
+        } else {
+            format!("+ {}", rng.range(1..=8))
+        };
+
+        let others: Vec<usize> = (0..size).filter(|other| *other != monkey).collect();
+        let if_true = *rng.pick(&others);
+        let if_false = match others.len() {
+            1 => if_true,
+            _ => loop {
+                let other = *rng.pick(&others);
+                if other != if_true {
+                    break other;
+                }
+            },
+        };
+
+        notes.push(format!(
+            "Monkey {monkey}:
+  Starting items: {}
+  Operation: new = old {operation}
+  Test: divisible by {}
+    If true: throw to monkey {if_true}
+    If false: throw to monkey {if_false}
+",
+            items.join(", "),
+            divisor
+        ));
+    }
+
+    notes.join("\n")
+}
+
+/// Whether the 20 rounds of part 1 keep every worry level within an [`Item`].
+fn fits(monkeys: &[Monkey]) -> bool {
+    simulate::<Item>(monkeys, 20, Relief::Divide(3)).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, SIZE), generate(3, SIZE));
+        for (seed, size) in (0..20).zip((1..).map(|size| size % 10)) {
+            let tokens = parse_input(&generate(seed, size)).unwrap();
+            assert_eq!(tokens.len(), size.clamp(2, PRIMES.len()));
+            part1(&tokens);
+            part2(&tokens);
+        }
+    }
+}
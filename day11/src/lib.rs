@@ -1,162 +1,482 @@
-use std::fs::File;
-use std::io::prelude::*;
+pub mod generate;
 
-type Tokens = String;
+use std::fmt;
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+use aoc_common::{BigUint, Line, Lines, ParseError, Solution};
 
-    let mut contents = String::with_capacity(10000);
+type Tokens = Vec<Monkey>;
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let mut monkeys = Tokens::new();
+    let mut targets = Vec::new();
+
+    for mut lines in Lines::new(11, input).blocks() {
+        lines.next_line("`Monkey N:`")?.all(|line| {
+            line.literal("Monkey ")?;
+            line.unsigned::<usize>("a monkey number")?;
+            line.literal(":")
+        })?;
+
+        let items = field(&mut lines, "Starting items:")?.all(|line| {
+            if line.is_empty() {
+                return Ok(Vec::new());
+            }
+            line.literal(" ")?;
+            line.separated(", ", |line| line.unsigned("a worry level"))
+        })?;
+
+        let operation = field(&mut lines, "Operation: new = old ")?.all(|line| {
+            if line.eat("* old") {
+                Ok(Operation::Square)
+            } else if line.eat("* ") {
+                Ok(Operation::Multiply(line.unsigned("a number")?))
+            } else if line.eat("+ ") {
+                Ok(Operation::Add(line.unsigned("a number")?))
+            } else {
+                Err(line.error("`* N`, `+ N` or `* old`"))
+            }
+        })?;
+
+        let divisor = field(&mut lines, "Test: divisible by ")?.all(|line| {
+            let before = *line;
+            match line.unsigned("a positive divisor")? {
+                0 => Err(before.error("a positive divisor")),
+                divisor => Ok(divisor),
+            }
+        })?;
+
+        let mut target = |name| {
+            field(&mut lines, name)?.all(|line| {
+                let before = *line;
+                let monkey = line.unsigned("a monkey number")?;
+                targets.push((before, monkey));
+                Ok(monkey)
+            })
+        };
+        let if_true = target("If true: throw to monkey ")?;
+        let if_false = target("If false: throw to monkey ")?;
+
+        monkeys.push(Monkey {
+            items,
+            operation,
+            divisor,
+            if_true,
+            if_false,
+        });
+    }
+
+    if let Some((line, _)) = targets.iter().find(|(_, monkey)| *monkey >= monkeys.len()) {
+        return Err(line.error("an existing monkey"));
+    }
+    if monkeys.len() < 2 {
+        return Err(ParseError::eof(11, input, "at least two monkeys"));
     }
+
+    Ok(monkeys)
 }
 
-pub fn parse_input(input: &String) -> Tokens {
-    input.clone()
+/// The next line of a monkey's block, past its indentation and `name`.
+fn field<'a>(lines: &mut Lines<'a>, name: &str) -> Result<Line<'a>, ParseError> {
+    let expected = format!("`{}`", name.trim_end());
+    let mut line = lines.next_line(&expected)?;
+    line.take_while(|ch| ch == ' ');
+    line.literal(name)?;
+    Ok(line)
 }
 
-pub fn get_answer(_tokens: &Tokens) -> i64 {
-    let mut monkeys = [
-        Monkey {
-            items: Vec::from([76, 88, 96, 97, 58, 61, 67]),
-            inspections: 0,
-            operation: |item| item * 19,
-            test: |item| {
-                if item % 3 == 0 {
-                    Throw { monkey: 2, item }
-                } else {
-                    Throw { monkey: 3, item }
-                }
-            },
-        },
-        Monkey {
-            items: Vec::from([93, 71, 79, 83, 69, 70, 94, 98]),
-            inspections: 0,
-            operation: |item| item + 8,
-            test: |item| {
-                if item % 11 == 0 {
-                    Throw { monkey: 5, item }
-                } else {
-                    Throw { monkey: 6, item }
-                }
-            },
-        },
-        Monkey {
-            items: Vec::from([50, 74, 67, 92, 61, 76]),
-            inspections: 0,
-            operation: |item| item * 13,
-            test: |item| {
-                if item % 19 == 0 {
-                    Throw { monkey: 3, item }
-                } else {
-                    Throw { monkey: 1, item }
-                }
-            },
-        },
-        Monkey {
-            items: Vec::from([76, 92]),
-            inspections: 0,
-            operation: |item| item + 6,
-            test: |item| {
-                if item % 5 == 0 {
-                    Throw { monkey: 1, item }
-                } else {
-                    Throw { monkey: 6, item }
-                }
-            },
-        },
-        Monkey {
-            items: Vec::from([74, 94, 55, 87, 62]),
-            inspections: 0,
-            operation: |item| item + 5,
-            test: |item| {
-                if item % 2 == 0 {
-                    Throw { monkey: 2, item }
-                } else {
-                    Throw { monkey: 0, item }
-                }
-            },
-        },
-        Monkey {
-            items: Vec::from([59, 62, 53, 62]),
-            inspections: 0,
-            operation: |item| item * item,
-            test: |item| {
-                if item % 7 == 0 {
-                    Throw { monkey: 4, item }
-                } else {
-                    Throw { monkey: 7, item }
-                }
-            },
-        },
-        Monkey {
-            items: Vec::from([62]),
-            inspections: 0,
-            operation: |item| item + 2,
-            test: |item| {
-                if item % 17 == 0 {
-                    Throw { monkey: 5, item }
-                } else {
-                    Throw { monkey: 7, item }
-                }
-            },
-        },
-        Monkey {
-            items: Vec::from([85, 54, 53]),
-            inspections: 0,
-            operation: |item| item + 3,
-            test: |item| {
-                if item % 13 == 0 {
-                    Throw { monkey: 4, item }
-                } else {
-                    Throw { monkey: 0, item }
-                }
-            },
-        },
-    ];
-
-    let supermodulo = 3 * 11 * 19 * 5 * 2 * 7 * 17 * 13;
-
-    for _ in 1..=10000 {
-        for i in 0..monkeys.len() {
-            let items = monkeys[i].items.clone();
-            monkeys[i].items.clear();
-            for item in items {
-                let mut new = (monkeys[i].operation)(item);
-                monkeys[i].inspections += 1;
-                new %= supermodulo;
-                let Throw { monkey, item } = (monkeys[i].test)(new);
-                monkeys[monkey].items.push(item);
+pub fn part1(tokens: &Tokens) -> i64 {
+    monkey_business(tokens, 20, Relief::Divide(3))
+}
+
+pub fn part2(tokens: &Tokens) -> i64 {
+    monkey_business(tokens, 10000, Relief::Modulo)
+}
+
+/// How worry levels drop after each inspection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relief {
+    /// Divided by the given number and rounded down, as in part 1.
+    Divide(Item),
+    /// Kept as the remainder by the product of every monkey's divisor, as in
+    /// part 2.
+    Modulo,
+    /// Not at all, worry levels grow without bound.
+    None,
+}
+
+/// Product of the two largest numbers of inspections after `rounds` rounds.
+///
+/// Worry levels are worked out in [`Item`]s, and redone with big integers
+/// when they outgrow them.
+pub fn monkey_business(tokens: &Tokens, rounds: usize, relief: Relief) -> i64 {
+    simulate::<Item>(tokens, rounds, relief)
+        .or_else(|| simulate::<BigUint>(tokens, rounds, relief))
+        .expect("big integers do not overflow")
+}
+
+/// Monkey business in worry levels of type `W`, `None` when a level, or the
+/// product of the divisors, outgrows it.
+fn simulate<W: Worry>(tokens: &[Monkey], rounds: usize, relief: Relief) -> Option<i64> {
+    let modulus = match relief {
+        Relief::Modulo => Some(W::product(tokens.iter().map(|monkey| monkey.divisor))?),
+        Relief::Divide(_) | Relief::None => None,
+    };
+    let mut items: Vec<Vec<W>> = tokens
+        .iter()
+        .map(|monkey| monkey.items.iter().map(|item| W::new(*item)).collect())
+        .collect();
+    let mut inspections = vec![0; tokens.len()];
+
+    for _ in 1..=rounds {
+        for (i, monkey) in tokens.iter().enumerate() {
+            for item in std::mem::take(&mut items[i]) {
+                let new = item
+                    .apply(&monkey.operation)?
+                    .relieve(relief, modulus.as_ref());
+                inspections[i] += 1;
+                let target = match new.divisible(monkey.divisor) {
+                    true => monkey.if_true,
+                    false => monkey.if_false,
+                };
+                items[target].push(new);
             }
         }
     }
 
-    let mut inspections: Vec<i64> = monkeys.iter().map(|m| m.inspections).collect();
-
     inspections.sort();
     inspections.reverse();
 
-    inspections[0] * inspections[1]
+    Some(inspections[0] * inspections[1])
 }
 
 type Item = i64;
 type MonekyId = usize;
 
-struct Throw {
-    monkey: MonekyId,
-    item: Item,
+/// Worry level arithmetic, on [`Item`]s checked for overflow or on big
+/// integers.
+trait Worry: Sized {
+    fn new(item: Item) -> Self;
+    /// The product of `factors`, `None` when it does not fit.
+    fn product(factors: impl Iterator<Item = Item>) -> Option<Self>;
+    /// The level after `operation`, `None` when it does not fit.
+    fn apply(&self, operation: &Operation) -> Option<Self>;
+    /// `modulus` is the product of the divisors with [`Relief::Modulo`].
+    fn relieve(self, relief: Relief, modulus: Option<&Self>) -> Self;
+    fn divisible(&self, divisor: Item) -> bool;
+}
+
+impl Worry for Item {
+    fn new(item: Item) -> Self {
+        item
+    }
+
+    fn product(mut factors: impl Iterator<Item = Item>) -> Option<Self> {
+        factors.try_fold(1, Item::checked_mul)
+    }
+
+    fn apply(&self, operation: &Operation) -> Option<Self> {
+        match *operation {
+            Operation::Add(value) => self.checked_add(value),
+            Operation::Multiply(value) => self.checked_mul(value),
+            Operation::Square => self.checked_mul(*self),
+        }
+    }
+
+    fn relieve(self, relief: Relief, modulus: Option<&Self>) -> Self {
+        match (relief, modulus) {
+            (Relief::Divide(by), _) => self / by,
+            (Relief::Modulo, Some(modulus)) => self % modulus,
+            _ => self,
+        }
+    }
+
+    fn divisible(&self, divisor: Item) -> bool {
+        self % divisor == 0
+    }
+}
+
+impl Worry for BigUint {
+    fn new(item: Item) -> Self {
+        BigUint::from(item as u64)
+    }
+
+    fn product(factors: impl Iterator<Item = Item>) -> Option<Self> {
+        Some(factors.fold(BigUint::from(1), |product, factor| {
+            &product * &Self::new(factor)
+        }))
+    }
+
+    fn apply(&self, operation: &Operation) -> Option<Self> {
+        match *operation {
+            Operation::Add(value) => Some(self + &BigUint::from(value as u64)),
+            Operation::Multiply(value) => Some(self * &BigUint::from(value as u64)),
+            Operation::Square => Some(self * self),
+        }
+    }
+
+    fn relieve(self, relief: Relief, modulus: Option<&Self>) -> Self {
+        match (relief, modulus) {
+            (Relief::Divide(by), _) => &self / by as u64,
+            (Relief::Modulo, Some(modulus)) => &self % modulus,
+            _ => self,
+        }
+    }
+
+    fn divisible(&self, divisor: Item) -> bool {
+        self % divisor as u64 == 0
+    }
 }
 
-#[derive(Debug)]
-struct Monkey {
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    Add(Item),
+    Multiply(Item),
+    Square,
+}
+
+/// The operation as written after `new = old `.
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add(value) => write!(f, "+ {value}"),
+            Self::Multiply(value) => write!(f, "* {value}"),
+            Self::Square => write!(f, "* old"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monkey {
     items: Vec<Item>,
-    inspections: i64,
-    operation: fn(Item) -> Item,
-    test: fn(Item) -> Throw,
+    operation: Operation,
+    divisor: Item,
+    if_true: MonekyId,
+    if_false: MonekyId,
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Tokens;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
 }
 
-pub const CORRECT_ANSWER: i64 = 54832778815;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_monkey() {
+        let monkeys = parse_input(EXAMPLE).unwrap();
+        assert_eq!(monkeys.len(), 4);
+        assert_eq!(
+            monkeys[2],
+            Monkey {
+                items: Vec::from([79, 60, 97]),
+                operation: Operation::Square,
+                divisor: 13,
+                if_true: 1,
+                if_false: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = EXAMPLE.replace("old * old", "old ^ 2");
+        assert_eq!(
+            parse_input(&input),
+            Err(ParseError::new(11, 17, 24, "^", "`* N`, `+ N` or `* old`"))
+        );
+        let input = EXAMPLE.replace("throw to monkey 0", "throw to monkey 4");
+        assert_eq!(
+            parse_input(&input),
+            Err(ParseError::new(11, 13, 31, "4", "an existing monkey"))
+        );
+        assert_eq!(
+            parse_input("Monkey 0:\n  Starting items: 79, 98\n"),
+            Err(ParseError::new(11, 3, 1, "", "`Operation: new = old`"))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 10605);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 2713310158);
+    }
+
+    #[test]
+    fn test_no_relief() {
+        let monkeys = parse_input(EXAMPLE).unwrap();
+        assert_eq!(monkey_business(&monkeys, 1, Relief::None), 4 * 6);
+        // well past an Item by round 20
+        assert_eq!(simulate::<Item>(&monkeys, 20, Relief::None), None);
+        assert_eq!(monkey_business(&monkeys, 20, Relief::None), 99 * 103);
+    }
+
+    #[test]
+    fn test_overflow_fallback() {
+        // the worry level is squared every round, 1000 soon outgrows an Item
+        let input = "Monkey 0:
+  Starting items: 1000
+  Operation: new = old * old
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 5
+  Operation: new = old + 1
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+        let monkeys = parse_input(input).unwrap();
+        assert_eq!(simulate::<Item>(&monkeys, 8, Relief::Divide(3)), None);
+        // monkey 0 starts with one item, then both pass on two a round
+        assert_eq!(monkey_business(&monkeys, 8, Relief::Divide(3)), 15 * 16);
+    }
+
+    #[test]
+    fn test_large_divisors() {
+        // the divisors' product is past an Item, so part 2 falls back to big
+        // integers; the answers come from keeping every item as its
+        // remainders by each divisor
+        for divisors in [
+            [1000003, 1000033, 1000037, 1000039],
+            [23, 1000033, 1000037, 1000039],
+        ] {
+            let mut monkeys = parse_input(EXAMPLE).unwrap();
+            for (monkey, divisor) in monkeys.iter_mut().zip(divisors) {
+                monkey.divisor = divisor;
+            }
+            assert_eq!(simulate::<Item>(&monkeys, 1, Relief::Modulo), None);
+            assert_eq!(part2(&monkeys), 50000 * 50000);
+        }
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day11>(300, |seed| generate::generate(seed, 5));
+    }
+
+    fn format_monkeys(monkeys: &[Monkey]) -> String {
+        let blocks: Vec<String> = monkeys
+            .iter()
+            .enumerate()
+            .map(|(i, monkey)| {
+                let items: Vec<String> = monkey.items.iter().map(Item::to_string).collect();
+                format!(
+                    "Monkey {i}:\n  Starting items: {}\n  Operation: new = old {}\n  \
+                     Test: divisible by {}\n    If true: throw to monkey {}\n    \
+                     If false: throw to monkey {}\n",
+                    items.join(", "),
+                    monkey.operation,
+                    monkey.divisor,
+                    monkey.if_true,
+                    monkey.if_false
+                )
+            })
+            .collect();
+        blocks.join("\n")
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let monkeys = parse_input(&generate::generate(seed, generate::SIZE)).unwrap();
+            assert_eq!(parse_input(&format_monkeys(&monkeys)).unwrap(), monkeys);
+        }
+    }
+
+    /// Part 1 on wide integers and part 2 keeping every item as its
+    /// remainders by each monkey's divisor instead of by their product.
+    #[test]
+    fn test_reference() {
+        fn business(mut inspections: Vec<i64>) -> i64 {
+            inspections.sort_by(|a, b| b.cmp(a));
+            inspections[0] * inspections[1]
+        }
+        fn apply(operation: &Operation, item: i128) -> i128 {
+            match *operation {
+                Operation::Add(value) => item + value as i128,
+                Operation::Multiply(value) => item * value as i128,
+                Operation::Square => item * item,
+            }
+        }
+
+        for seed in 0..4 {
+            let monkeys = parse_input(&generate::generate(seed, 5)).unwrap();
+
+            let mut items: Vec<Vec<i128>> = monkeys
+                .iter()
+                .map(|monkey| monkey.items.iter().map(|item| *item as i128).collect())
+                .collect();
+            let mut inspections = vec![0; monkeys.len()];
+            for _ in 0..20 {
+                for (i, monkey) in monkeys.iter().enumerate() {
+                    for item in std::mem::take(&mut items[i]) {
+                        inspections[i] += 1;
+                        let new = apply(&monkey.operation, item) / 3;
+                        let to = match new % monkey.divisor as i128 {
+                            0 => monkey.if_true,
+                            _ => monkey.if_false,
+                        };
+                        items[to].push(new);
+                    }
+                }
+            }
+            assert_eq!(part1(&monkeys), business(inspections));
+
+            let divisors: Vec<i128> = monkeys.iter().map(|m| m.divisor as i128).collect();
+            let mut items: Vec<Vec<Vec<i128>>> = monkeys
+                .iter()
+                .map(|monkey| {
+                    (monkey.items.iter())
+                        .map(|item| divisors.iter().map(|d| *item as i128 % d).collect())
+                        .collect()
+                })
+                .collect();
+            let mut inspections = vec![0; monkeys.len()];
+            for _ in 0..10000 {
+                for (i, monkey) in monkeys.iter().enumerate() {
+                    for residues in std::mem::take(&mut items[i]) {
+                        inspections[i] += 1;
+                        let new: Vec<i128> = residues
+                            .iter()
+                            .zip(&divisors)
+                            .map(|(residue, d)| apply(&monkey.operation, *residue) % d)
+                            .collect();
+                        let to = match new[i] {
+                            0 => monkey.if_true,
+                            _ => monkey.if_false,
+                        };
+                        items[to].push(new);
+                    }
+                }
+            }
+            assert_eq!(part2(&monkeys), business(inspections));
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use aoc_common::Rng;
+
+/// Rows in the puzzle input's height map.
+pub const SIZE: usize = 41;
+
+/// A height map `size` rows high, at least 14, and four times as wide.
+///
+/// A path walking right and down from `S` in the top left corner climbs
+/// evenly to `E` in the bottom right corner, so the end can always be
+/// reached, and the rest is random terrain.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let height = size.max(14);
+    let width = 4 * height;
+
+    let mut cells: Vec<Vec<u8>> = (0..height)
+        .map(|_| (0..width).map(|_| b'a' + rng.below(26) as u8).collect())
+        .collect();
+
+    let steps = width + height - 2;
+    let (mut x, mut y) = (0, 0);
+    for step in 0..=steps {
+        cells[y][x] = b'a' + (step * 25 / steps) as u8;
+        if x == width - 1 || (y < height - 1 && rng.chance(height as u64, steps as u64)) {
+            y += 1;
+        } else {
+            x += 1;
+        }
+    }
+    cells[0][0] = b'S';
+    cells[height - 1][width - 1] = b'E';
+
+    let mut input = String::new();
+    for row in cells {
+        input.extend(row.into_iter().map(char::from));
+        input.push('\n');
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 14), generate(3, 14));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 14)).unwrap();
+            assert_eq!((tokens.width(), tokens.height()), (56, 14));
+            assert!(part2(&tokens) <= part1(&tokens));
+        }
+    }
+}
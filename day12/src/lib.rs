@@ -1,382 +1,136 @@
-use std::collections::HashMap;
-use std::io::prelude::*;
-use std::{collections::HashSet, fs::File};
+pub mod generate;
 
+use aoc_common::{Grid, Image, ParseError, Point, Solution};
 use petgraph::algo::dijkstra;
-use petgraph::graph::Graph;
-
-type Tokens = Vec<Vec<u8>>;
+use petgraph::graph::{Graph, NodeIndex};
+
+type Tokens = Grid<u8>;
+
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let tokens = Grid::parse(12, input, "an elevation letter", |ch| match ch {
+        'a'..='z' | 'S' | 'E' => Some(ch as u8),
+        _ => None,
+    })?;
+
+    for marker in [b'S', b'E'] {
+        let mut markers = tokens.iter().filter(|(_, cell)| **cell == marker);
+        if let Some((point, _)) = markers.nth(1) {
+            let text = (marker as char).to_string();
+            let (line, column) = (point.y as usize + 1, point.x as usize + 1);
+            return Err(ParseError::new(12, line, column, &text, "a single S and E"));
+        }
+    }
+    for (marker, expected) in [(b'S', "a start S"), (b'E', "an end E")] {
+        if tokens.position(|cell| *cell == marker).is_none() {
+            return Err(ParseError::eof(12, input, expected));
+        }
+    }
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+    Ok(tokens)
+}
 
-    let mut contents = String::with_capacity(10000);
+/// Fewest steps from S up to E, `None` when E cannot be reached.
+pub fn part1(tokens: &Tokens) -> Option<i32> {
+    let start = tokens
+        .position(|cell| *cell == b'S')
+        .expect("parse_input checks there is an S");
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
-    }
+    HeightMap::new(tokens).steps_from(start)
 }
 
-pub fn parse_input(_input: &String) -> Tokens {
-    let mut tokens = Tokens::new();
-
-    let mut tmp = Vec::new();
-    for ch in _input.chars() {
-        if ch != '\n' {
-            tmp.push(ch as u8);
-        } else {
-            tokens.push(tmp.clone());
-            tmp.clear();
-        }
-    }
+/// Fewest steps from any lowest cell up to E, `None` when E cannot be reached
+/// from any of them.
+pub fn part2(tokens: &Tokens) -> Option<i32> {
+    let map = HeightMap::new(tokens);
 
     tokens
+        .iter()
+        .filter(|(_, elevation)| elevation_of(**elevation) == b'a')
+        .filter_map(|(start, _)| map.steps_from(start))
+        .min()
 }
 
-pub fn get_answer(tokens: &Tokens) -> i32 {
-    let mut possible = Vec::new();
-    let height = tokens.len();
-    let width = tokens[0].len();
-
-    for i in 0..height {
-        for j in 0..width {
-            let mut tokens = tokens.clone();
-            if tokens[i][j] == 'a' as u8 {
-                tokens[i][j] = 'S' as u8;
-            } else {
-                continue;
-            }
-            match calculate(&tokens) {
-                Some(n) => {
-                    possible.push(n);
-                    continue;
-                }
-                None => continue,
-            }
+/// Elevations shaded from dark to light green, the start in red and the end in
+/// blue, the same for both parts.
+pub fn image(tokens: &Tokens, _part: u8) -> Image {
+    Image::Colour(tokens.map(|cell| match *cell {
+        b'S' => [255, 0, 0],
+        b'E' => [0, 0, 255],
+        elevation => {
+            let shade = (elevation - b'a') * 9;
+            [shade / 2, 30 + shade, shade / 2]
         }
-    }
+    }))
+}
 
-    possible.sort();
-    possible[0]
+fn elevation_of(cell: u8) -> u8 {
+    match cell {
+        b'S' => b'a',
+        b'E' => b'z',
+        elevation => elevation,
+    }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-struct Point {
-    elevation: u8,
-    coordinates: (usize, usize),
+/// Graph of the allowed steps, with one node per cell numbered by
+/// [`Grid::index_of`].
+struct HeightMap<'a> {
+    tokens: &'a Tokens,
+    graph: Graph<Point, i32>,
+    end: NodeIndex,
 }
 
-fn calculate(tokens: &Tokens) -> Option<i32> {
-    let height = tokens.len();
-    let width = tokens[0].len();
-
-    let tokens = tokens.clone();
-
-    let mut edges = HashSet::new();
-    let mut g = Graph::new();
-    let mut id_to_node_index = HashMap::new();
-    let mut node_index_to_id = HashMap::new();
-
-    let mut s_id = 0;
-    let mut e_id = 0;
-
-    for i in 0..height {
-        for j in 0..width {
-            let middle_id = j + i * width;
-            let middle_elevation = {
-                if tokens[i][j] == 'S' as u8 {
-                    s_id = middle_id;
-                    'a' as u8
-                } else if tokens[i][j] == 'E' as u8 {
-                    e_id = middle_id;
-                    'z' as u8
-                } else {
-                    tokens[i][j]
-                }
-            };
+impl<'a> HeightMap<'a> {
+    fn new(tokens: &'a Tokens) -> Self {
+        let mut graph = Graph::new();
+        for point in tokens.points() {
+            graph.add_node(point);
+        }
 
-            if j != width - 1 {
-                let right_id = middle_id + 1;
-                let right_elevation = {
-                    if tokens[i][j + 1] == 'S' as u8 {
-                        'a' as u8
-                    } else if tokens[i][j + 1] == 'E' as u8 {
-                        'z' as u8
-                    } else {
-                        tokens[i][j + 1]
-                    }
-                };
-                if (middle_elevation).abs_diff(right_elevation) <= 1 {
-                    if edges.insert((middle_id, right_id)) && edges.insert((right_id, middle_id)) {
-                        let a = match id_to_node_index.get(&middle_id) {
-                            Some(node_index) => *node_index,
-                            None => {
-                                let tmp = g.add_node(middle_id);
-                                id_to_node_index.insert(middle_id, tmp);
-                                node_index_to_id.insert(tmp, middle_id);
-                                tmp
-                            }
-                        };
-                        let b = match id_to_node_index.get(&right_id) {
-                            Some(node_index) => *node_index,
-                            None => {
-                                let tmp = g.add_node(right_id);
-                                id_to_node_index.insert(right_id, tmp);
-                                node_index_to_id.insert(tmp, right_id);
-                                node_index_to_id.insert(tmp, right_id);
-                                tmp
-                            }
-                        };
-                        g.add_edge(a, b, 1);
-                        g.add_edge(b, a, 1);
-                    }
-                } else if !edges.contains(&(middle_id, right_id))
-                    && !edges.contains(&(right_id, middle_id))
-                {
-                    let a = match id_to_node_index.get(&middle_id) {
-                        Some(node_index) => *node_index,
-                        None => {
-                            let tmp = g.add_node(middle_id);
-                            id_to_node_index.insert(middle_id, tmp);
-                            node_index_to_id.insert(tmp, middle_id);
-                            tmp
-                        }
-                    };
-                    let b = match id_to_node_index.get(&right_id) {
-                        Some(node_index) => *node_index,
-                        None => {
-                            let tmp = g.add_node(right_id);
-                            id_to_node_index.insert(right_id, tmp);
-                            node_index_to_id.insert(tmp, right_id);
-                            node_index_to_id.insert(tmp, right_id);
-                            tmp
-                        }
-                    };
-
-                    if middle_elevation > right_elevation {
-                        g.add_edge(a, b, 1);
-                        edges.insert((middle_id, right_id));
-                    } else {
-                        g.add_edge(b, a, 1);
-                        edges.insert((right_id, middle_id));
-                    }
+        for (point, cell) in tokens.iter() {
+            let node = NodeIndex::new(tokens.index_of(point).unwrap());
+            for neighbour in tokens.neighbours4(point) {
+                // climbing is limited to one step, descending is not
+                if elevation_of(tokens[neighbour]) <= elevation_of(*cell) + 1 {
+                    let next = NodeIndex::new(tokens.index_of(neighbour).unwrap());
+                    graph.add_edge(node, next, 1);
                 }
             }
+        }
 
-            if j != 0 {
-                let left_id = middle_id - 1;
-                let left_elevation = {
-                    if tokens[i][j - 1] == 'S' as u8 {
-                        'a' as u8
-                    } else if tokens[i][j - 1] == 'E' as u8 {
-                        'z' as u8
-                    } else {
-                        tokens[i][j - 1]
-                    }
-                };
-                if (middle_elevation).abs_diff(left_elevation) <= 1 {
-                    if edges.insert((middle_id, left_id)) && edges.insert((left_id, middle_id)) {
-                        let a = match id_to_node_index.get(&middle_id) {
-                            Some(node_index) => *node_index,
-                            None => {
-                                let tmp = g.add_node(middle_id);
-                                id_to_node_index.insert(middle_id, tmp);
-                                node_index_to_id.insert(tmp, middle_id);
-                                tmp
-                            }
-                        };
-                        let b = match id_to_node_index.get(&left_id) {
-                            Some(node_index) => *node_index,
-                            None => {
-                                let tmp = g.add_node(left_id);
-                                id_to_node_index.insert(left_id, tmp);
-                                node_index_to_id.insert(tmp, left_id);
-                                tmp
-                            }
-                        };
-                        g.add_edge(a, b, 1);
-                        g.add_edge(b, a, 1);
-                    }
-                } else if !edges.contains(&(middle_id, left_id))
-                    && !edges.contains(&(left_id, middle_id))
-                {
-                    let a = match id_to_node_index.get(&middle_id) {
-                        Some(node_index) => *node_index,
-                        None => {
-                            let tmp = g.add_node(middle_id);
-                            id_to_node_index.insert(middle_id, tmp);
-                            node_index_to_id.insert(tmp, middle_id);
-                            tmp
-                        }
-                    };
-                    let b = match id_to_node_index.get(&left_id) {
-                        Some(node_index) => *node_index,
-                        None => {
-                            let tmp = g.add_node(left_id);
-                            id_to_node_index.insert(left_id, tmp);
-                            node_index_to_id.insert(tmp, left_id);
-                            node_index_to_id.insert(tmp, left_id);
-                            tmp
-                        }
-                    };
-
-                    if middle_elevation > left_elevation {
-                        g.add_edge(a, b, 1);
-                        edges.insert((middle_id, left_id));
-                    } else {
-                        g.add_edge(b, a, 1);
-                        edges.insert((left_id, middle_id));
-                    }
-                }
-            }
+        let end = tokens.position(|cell| *cell == b'E').unwrap();
+        let end = NodeIndex::new(tokens.index_of(end).unwrap());
 
-            if i != 0 {
-                let up_id = middle_id - width;
-                let up_elevation = {
-                    if tokens[i - 1][j] == 'S' as u8 {
-                        'a' as u8
-                    } else if tokens[i - 1][j] == 'E' as u8 {
-                        'z' as u8
-                    } else {
-                        tokens[i - 1][j]
-                    }
-                };
-                if (middle_elevation).abs_diff(up_elevation) <= 1 {
-                    if edges.insert((middle_id, up_id)) && edges.insert((up_id, middle_id)) {
-                        let a = match id_to_node_index.get(&middle_id) {
-                            Some(node_index) => *node_index,
-                            None => {
-                                let tmp = g.add_node(middle_id);
-                                id_to_node_index.insert(middle_id, tmp);
-                                node_index_to_id.insert(tmp, middle_id);
-                                tmp
-                            }
-                        };
-                        let b = match id_to_node_index.get(&up_id) {
-                            Some(node_index) => *node_index,
-                            None => {
-                                let tmp = g.add_node(up_id);
-                                id_to_node_index.insert(up_id, tmp);
-                                node_index_to_id.insert(tmp, up_id);
-                                tmp
-                            }
-                        };
-                        g.add_edge(a, b, 1);
-                        g.add_edge(b, a, 1);
-                    }
-                } else if !edges.contains(&(middle_id, up_id))
-                    && !edges.contains(&(up_id, middle_id))
-                {
-                    let a = match id_to_node_index.get(&middle_id) {
-                        Some(node_index) => *node_index,
-                        None => {
-                            let tmp = g.add_node(middle_id);
-                            id_to_node_index.insert(middle_id, tmp);
-                            node_index_to_id.insert(tmp, middle_id);
-                            tmp
-                        }
-                    };
-                    let b = match id_to_node_index.get(&up_id) {
-                        Some(node_index) => *node_index,
-                        None => {
-                            let tmp = g.add_node(up_id);
-                            id_to_node_index.insert(up_id, tmp);
-                            node_index_to_id.insert(tmp, up_id);
-                            node_index_to_id.insert(tmp, up_id);
-                            tmp
-                        }
-                    };
-
-                    if middle_elevation > up_elevation {
-                        g.add_edge(a, b, 1);
-                        edges.insert((middle_id, up_id));
-                    } else {
-                        g.add_edge(b, a, 1);
-                        edges.insert((up_id, middle_id));
-                    }
-                }
-            }
+        Self { tokens, graph, end }
+    }
 
-            if i != height - 1 {
-                let down_id = middle_id + width;
-                let down_elevation = {
-                    if tokens[i + 1][j] == 'S' as u8 {
-                        'a' as u8
-                    } else if tokens[i + 1][j] == 'E' as u8 {
-                        'z' as u8
-                    } else {
-                        tokens[i + 1][j]
-                    }
-                };
-                if (middle_elevation).abs_diff(down_elevation) <= 1 {
-                    if edges.insert((middle_id, down_id)) && edges.insert((down_id, middle_id)) {
-                        let a = match id_to_node_index.get(&middle_id) {
-                            Some(node_index) => *node_index,
-                            None => {
-                                let tmp = g.add_node(middle_id);
-                                id_to_node_index.insert(middle_id, tmp);
-                                node_index_to_id.insert(tmp, middle_id);
-                                tmp
-                            }
-                        };
-                        let b = match id_to_node_index.get(&down_id) {
-                            Some(node_index) => *node_index,
-                            None => {
-                                let tmp = g.add_node(down_id);
-                                id_to_node_index.insert(down_id, tmp);
-                                node_index_to_id.insert(tmp, down_id);
-                                tmp
-                            }
-                        };
-                        g.add_edge(a, b, 1);
-                        g.add_edge(b, a, 1);
-                    }
-                } else if !edges.contains(&(middle_id, down_id))
-                    && !edges.contains(&(down_id, middle_id))
-                {
-                    let a = match id_to_node_index.get(&middle_id) {
-                        Some(node_index) => *node_index,
-                        None => {
-                            let tmp = g.add_node(middle_id);
-                            id_to_node_index.insert(middle_id, tmp);
-                            node_index_to_id.insert(tmp, middle_id);
-                            tmp
-                        }
-                    };
-                    let b = match id_to_node_index.get(&down_id) {
-                        Some(node_index) => *node_index,
-                        None => {
-                            let tmp = g.add_node(down_id);
-                            id_to_node_index.insert(down_id, tmp);
-                            node_index_to_id.insert(tmp, down_id);
-                            node_index_to_id.insert(tmp, down_id);
-                            tmp
-                        }
-                    };
-
-                    if middle_elevation > down_elevation {
-                        g.add_edge(a, b, 1);
-                        edges.insert((middle_id, down_id));
-                    } else {
-                        g.add_edge(b, a, 1);
-                        edges.insert((down_id, middle_id));
-                    }
-                }
-            }
-        }
+    fn steps_from(&self, start: Point) -> Option<i32> {
+        let start = NodeIndex::new(self.tokens.index_of(start)?);
+
+        dijkstra(&self.graph, start, Some(self.end), |_| 1)
+            .get(&self.end)
+            .copied()
     }
+}
+
+pub struct Day12;
 
-    let start_node_index = id_to_node_index.get(&s_id).unwrap().clone();
+impl Solution for Day12 {
+    const DAY: u8 = 12;
 
-    let end_node_index = id_to_node_index.get(&e_id).unwrap().clone();
+    type Input = Tokens;
+    type Output1 = Option<i32>;
+    type Output2 = Option<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
 
-    let res = dijkstra(&g, start_node_index, None, |_| 1);
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
 
-    match res.get(&end_node_index) {
-        Some(n) => return Some(*n),
-        None => return None,
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
 }
 
@@ -393,8 +147,9 @@ accszExk
 acctuvwj
 abdefghi
 ",
-        ));
-        let a = calculate(&tokens);
+        ))
+        .unwrap();
+        let a = HeightMap::new(&tokens).steps_from(Point::new(0, 0));
 
         assert_eq!(a.unwrap(), 31);
     }
@@ -404,11 +159,122 @@ abdefghi
         let tokens = parse_input(&String::from(
             "SabcabcdefghijklmnopqrstuvwxyzE
 ",
-        ));
-        let a = calculate(&tokens);
+        ))
+        .unwrap();
+        let a = HeightMap::new(&tokens).steps_from(Point::new(0, 0));
 
         assert_eq!(a.unwrap(), 30);
     }
-}
 
-pub const CORRECT_ANSWER: i32 = 430;
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("Sab\nc-E\n"),
+            Err(ParseError::new(12, 2, 2, "-", "an elevation letter"))
+        );
+        assert_eq!(
+            parse_input("Sab\ncEE\n"),
+            Err(ParseError::new(12, 2, 3, "E", "a single S and E"))
+        );
+        assert_eq!(
+            parse_input("Sab\ncd\n"),
+            Err(ParseError::new(12, 2, 1, "cd", "a rectangular grid"))
+        );
+        assert_eq!(
+            parse_input("Sab\ncde\n"),
+            Err(ParseError::new(12, 3, 1, "", "an end E"))
+        );
+        assert_eq!(
+            parse_input("bzE\n"),
+            Err(ParseError::new(12, 2, 1, "", "a start S"))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), Some(31));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), Some(29));
+    }
+
+    #[test]
+    fn test_no_path() {
+        let tokens = parse_input("Sab\ncdE\n").unwrap();
+        assert_eq!((part1(&tokens), part2(&tokens)), (None, None));
+        let tokens = parse_input("SzabcdefghijklmnopqrstuvwxyE\n").unwrap();
+        assert_eq!((part1(&tokens), part2(&tokens)), (None, Some(25)));
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day12>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text = tokens.map(|cell| *cell as char).to_string();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Walks back from the end breadth first over the rows of the input.
+    #[test]
+    fn test_reference() {
+        use std::collections::VecDeque;
+
+        for seed in 0..20 {
+            let input = generate::generate(seed, 10);
+            let rows: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+            let height = |(x, y): (usize, usize)| match rows[y][x] {
+                b'S' => b'a',
+                b'E' => b'z',
+                elevation => elevation,
+            };
+
+            let mut steps = vec![vec![None; rows[0].len()]; rows.len()];
+            let mut queue = VecDeque::new();
+            for (y, row) in rows.iter().enumerate() {
+                if let Some(x) = row.iter().position(|cell| *cell == b'E') {
+                    steps[y][x] = Some(0);
+                    queue.push_back((x, y));
+                }
+            }
+            while let Some((x, y)) = queue.pop_front() {
+                let here = steps[y][x].unwrap();
+                let next = [
+                    (x.wrapping_sub(1), y),
+                    (x + 1, y),
+                    (x, y.wrapping_sub(1)),
+                    (x, y + 1),
+                ];
+                for (nx, ny) in next {
+                    let inside = ny < rows.len() && nx < rows[0].len();
+                    if inside && steps[ny][nx].is_none() && height((x, y)) <= height((nx, ny)) + 1 {
+                        steps[ny][nx] = Some(here + 1);
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+
+            let from = |start: u8| {
+                (0..rows.len())
+                    .flat_map(|y| (0..rows[0].len()).map(move |x| (x, y)))
+                    .filter(|(x, y)| {
+                        rows[*y][*x] == start || (start == b'a' && rows[*y][*x] == b'S')
+                    })
+                    .filter_map(|(x, y)| steps[y][x])
+                    .min()
+            };
+            let tokens = parse_input(&input).unwrap();
+            assert_eq!(part1(&tokens), from(b'S'));
+            assert_eq!(part2(&tokens), from(b'a'));
+        }
+    }
+}
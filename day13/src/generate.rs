@@ -0,0 +1,57 @@
+use aoc_common::Rng;
+
+/// Pairs of packets in the puzzle input.
+pub const SIZE: usize = 150;
+
+/// `size` pairs of packets, lists nested up to five deep holding numbers up
+/// to 10.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    let pairs: Vec<String> = (0..size)
+        .map(|_| {
+            let mut pair = String::new();
+            for _ in 0..2 {
+                list(&mut rng, 5, &mut pair);
+                pair.push('\n');
+            }
+            pair
+        })
+        .collect();
+
+    pairs.join("\n")
+}
+
+fn list(rng: &mut Rng, depth: u32, packet: &mut String) {
+    packet.push('[');
+    for i in 0..rng.range(0..=5) {
+        if i > 0 {
+            packet.push(',');
+        }
+        if depth > 1 && rng.chance(1, 3) {
+            list(rng, depth - 1, packet);
+        } else {
+            packet.push_str(&rng.range(0..=10).to_string());
+        }
+    }
+    packet.push(']');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 30), generate(3, 30));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 30)).unwrap();
+            assert_eq!(tokens.len(), 60);
+            // the indices of all 30 pairs add up to 465
+            assert!(part1(&tokens) <= 465);
+            // the dividers are somewhere among the 62 packets
+            assert!((2..=61 * 62).contains(&part2(&tokens)));
+        }
+    }
+}
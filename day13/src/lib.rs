@@ -1,6 +1,9 @@
+pub mod generate;
+
 use std::cmp::Ordering;
-use std::fs::File;
-use std::io::prelude::*;
+use std::fmt;
+
+use aoc_common::{Line, Lines, ParseError, Solution};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Token {
@@ -9,67 +12,92 @@ pub enum Token {
     Number(i32),
 }
 
+/// A token as written in a packet, without the commas between elements.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::ListStart => write!(f, "["),
+            Token::ListEnd => write!(f, "]"),
+            Token::Number(number) => write!(f, "{number}"),
+        }
+    }
+}
+
 type Tokens = Vec<Vec<Token>>;
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+/// How deeply lists may nest. Comparing packets recurses once per level, so
+/// deeper ones are rejected rather than overflowing the stack.
+const MAX_DEPTH: usize = 100;
+
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let tokens: Tokens = Lines::new(13, input)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.all(|line| {
+                let mut tokens = Vec::new();
+                parse_list(line, &mut tokens, 1)?;
+                Ok(tokens)
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    // part 1 compares the packets in pairs
+    if !tokens.len().is_multiple_of(2) {
+        return Err(ParseError::eof(
+            13,
+            input,
+            "a second packet for the last pair",
+        ));
+    }
 
-    let mut contents = String::with_capacity(10000);
+    Ok(tokens)
+}
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
+fn parse_list(line: &mut Line, tokens: &mut Vec<Token>, depth: usize) -> Result<(), ParseError> {
+    if depth > MAX_DEPTH {
+        return Err(line.error(&format!("lists nested at most {MAX_DEPTH} deep")));
     }
+    line.literal("[")?;
+    tokens.push(Token::ListStart);
+
+    if !line.eat("]") {
+        loop {
+            if line.rest().starts_with('[') {
+                parse_list(line, tokens, depth + 1)?;
+            } else if line.eat("a") {
+                tokens.push(Token::Number(10));
+            } else {
+                tokens.push(Token::Number(line.unsigned("a number")?));
+            }
+
+            if line.eat("]") {
+                break;
+            }
+            if !line.eat(",") {
+                return Err(line.error("a , or ]"));
+            }
+        }
+    }
+
+    tokens.push(Token::ListEnd);
+    Ok(())
 }
 
-pub fn parse_input(input: &String) -> Tokens {
-    let mut all_tokens = Tokens::new();
+pub fn part1(tokens: &Tokens) -> usize {
+    let mut sum = 0;
 
-    for line in input.lines() {
-        if line.is_empty() {
-            continue;
-        }
-        let mut tokens = Vec::<Token>::new();
-        let mut ch_iter = line.chars().into_iter();
-        let mut number = String::new();
-        while let Some(ch) = ch_iter.next() {
-            match ch {
-                '[' => {
-                    assert!(number.is_empty());
-                    tokens.push(Token::ListStart)
-                }
-                ']' => {
-                    if !number.is_empty() {
-                        if number == "a" {
-                            tokens.push(Token::Number(10));
-                        } else {
-                            tokens.push(Token::Number(number.parse().unwrap()));
-                        }
-                        number.clear();
-                    }
-                    tokens.push(Token::ListEnd)
-                }
-                ',' => {
-                    if !number.is_empty() {
-                        if number == "a" {
-                            tokens.push(Token::Number(10));
-                        } else {
-                            tokens.push(Token::Number(number.parse().unwrap()));
-                        }
-                        number.clear();
-                    }
-                }
-                ch => number.push(ch),
+    for (index, pair) in tokens.chunks(2).enumerate() {
+        if let [first, second] = pair {
+            if build_recursion(first) < build_recursion(second) {
+                sum += index + 1;
             }
         }
-        all_tokens.push(tokens);
     }
 
-    all_tokens
+    sum
 }
 
-pub fn get_answer(tokens: &Tokens) -> usize {
+pub fn part2(tokens: &Tokens) -> usize {
     let mut tokens = tokens.clone();
     let two = Vec::from([
         Token::ListStart,
@@ -115,9 +143,15 @@ enum Element {
 }
 
 impl PartialOrd for Element {
-    fn partial_cmp(&self, other: &Element) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Element) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Element {
+    fn cmp(&self, other: &Self) -> Ordering {
         match (self, other) {
-            (Self::Number(s), Self::Number(o)) => s.partial_cmp(o),
+            (Self::Number(s), Self::Number(o)) => s.cmp(o),
             (Self::List(s), Self::List(o)) => {
                 let mut s = s.iter();
                 let mut o = o.iter();
@@ -125,35 +159,28 @@ impl PartialOrd for Element {
                 loop {
                     match (s.next(), o.next()) {
                         (Some(a), Some(b)) => {
-                            if let Some(ordering) = a.partial_cmp(b) {
-                                if ordering != Ordering::Equal {
-                                    return Some(ordering);
-                                }
+                            let ordering = a.cmp(b);
+                            if ordering != Ordering::Equal {
+                                return ordering;
                             }
                         }
-                        (Some(_), None) => return Some(Ordering::Greater),
-                        (None, Some(_)) => return Some(Ordering::Less),
-                        (None, None) => return Some(Ordering::Equal),
+                        (Some(_), None) => return Ordering::Greater,
+                        (None, Some(_)) => return Ordering::Less,
+                        (None, None) => return Ordering::Equal,
                     }
                 }
             }
             (Self::Number(a), Self::List(_)) => {
-                Self::List(Vec::from([Self::Number(*a)])).partial_cmp(other)
+                Self::List(Vec::from([Self::Number(*a)])).cmp(other)
             }
             (Self::List(_), Self::Number(b)) => {
-                self.partial_cmp(&Self::List(Vec::from([Self::Number(*b)])))
+                self.cmp(&Self::List(Vec::from([Self::Number(*b)])))
             }
         }
     }
 }
 
-impl Ord for Element {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
-fn build_recursion(tokens: &Vec<Token>) -> Element {
+fn build_recursion(tokens: &[Token]) -> Element {
     let mut vec_of_elements = Vec::new();
 
     // find start_index and end_index of outermost list
@@ -173,11 +200,8 @@ fn build_recursion(tokens: &Vec<Token>) -> Element {
                 list_stack -= 1;
                 if list_stack == 0 {
                     // found inner list, build Element from it !
-                    let mut tmp = Vec::new();
-                    for j in start_index.unwrap() + 1..i {
-                        tmp.push(tokens[j].clone());
-                    }
-                    vec_of_elements.push(build_recursion(&tmp));
+                    let inner = &tokens[start_index.unwrap() + 1..i];
+                    vec_of_elements.push(build_recursion(inner));
                     start_index = None;
                 }
             }
@@ -194,6 +218,28 @@ fn build_recursion(tokens: &Vec<Token>) -> Element {
     Element::List(vec_of_elements)
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = Tokens;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,7 +345,7 @@ mod tests {
             Token::ListEnd,
         ]);
 
-        assert_eq!(build_recursion(&first) < build_recursion(&second), true);
+        assert!(build_recursion(&first) < build_recursion(&second));
     }
 
     #[test]
@@ -325,7 +371,7 @@ mod tests {
             Token::ListEnd,
         ]);
 
-        assert_eq!(build_recursion(&first) < build_recursion(&second), true);
+        assert!(build_recursion(&first) < build_recursion(&second));
     }
 
     #[test]
@@ -341,7 +387,7 @@ mod tests {
             Token::ListEnd,
         ]);
 
-        assert_eq!(build_recursion(&first) < build_recursion(&second), false);
+        assert!(build_recursion(&first) >= build_recursion(&second));
     }
 
     #[test]
@@ -368,7 +414,7 @@ mod tests {
             Token::ListEnd,
         ]);
 
-        assert_eq!(build_recursion(&first) < build_recursion(&second), true);
+        assert!(build_recursion(&first) < build_recursion(&second));
     }
 
     #[test]
@@ -389,7 +435,7 @@ mod tests {
             Token::ListEnd,
         ]);
 
-        assert_eq!(build_recursion(&first) < build_recursion(&second), false);
+        assert!(build_recursion(&first) >= build_recursion(&second));
     }
 
     #[test]
@@ -397,7 +443,7 @@ mod tests {
         let first = Vec::from([Token::ListStart, Token::ListEnd]);
         let second = Vec::from([Token::ListStart, Token::Number(3), Token::ListEnd]);
 
-        assert_eq!(build_recursion(&first) < build_recursion(&second), true);
+        assert!(build_recursion(&first) < build_recursion(&second));
     }
 
     #[test]
@@ -417,7 +463,7 @@ mod tests {
             Token::ListEnd,
         ]);
 
-        assert_eq!(build_recursion(&first) < build_recursion(&second), false);
+        assert!(build_recursion(&first) >= build_recursion(&second));
     }
 
     #[test]
@@ -465,8 +511,173 @@ mod tests {
             Token::ListEnd,
         ]);
 
-        assert_eq!(build_recursion(&first) < build_recursion(&second), false);
+        assert!(build_recursion(&first) >= build_recursion(&second));
     }
-}
 
-pub const CORRECT_ANSWER: usize = 25800;
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("[1,[2,x]]\n"),
+            Err(ParseError::new(13, 1, 7, "x", "a number"))
+        );
+        assert_eq!(
+            parse_input("[1,2]\n\n[[3]\n"),
+            Err(ParseError::new(13, 3, 5, "", "a , or ]"))
+        );
+        assert_eq!(
+            parse_input("[1]]\n"),
+            Err(ParseError::new(13, 1, 4, "]", "the end of the line"))
+        );
+        assert_eq!(
+            parse_input(
+                "[1]
+[2]
+
+[3]
+"
+            ),
+            Err(ParseError::new(
+                13,
+                5,
+                1,
+                "",
+                "a second packet for the last pair"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_nesting() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        let tokens = parse_input(&(nested(MAX_DEPTH) + "\n" + &nested(MAX_DEPTH))).unwrap();
+        assert_eq!((part1(&tokens), part2(&tokens)), (0, 12));
+        assert_eq!(
+            parse_input(&nested(200000)),
+            Err(ParseError::new(
+                13,
+                1,
+                MAX_DEPTH + 1,
+                "[",
+                "lists nested at most 100 deep"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 13);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 140);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day13>(300, |seed| generate::generate(seed, 5));
+    }
+
+    fn format_packet(tokens: &[Token]) -> String {
+        let mut packet = String::new();
+        for (i, token) in tokens.iter().enumerate() {
+            if i > 0 && tokens[i - 1] != Token::ListStart && *token != Token::ListEnd {
+                packet.push(',');
+            }
+            packet += &token.to_string();
+        }
+        packet
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            let packets: Vec<String> = tokens.iter().map(|packet| format_packet(packet)).collect();
+            let text: String = packets
+                .chunks(2)
+                .map(|pair| format!("{}\n{}\n", pair[0], pair[1]))
+                .collect::<Vec<_>>()
+                .join("\n");
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Compares the packets' text directly, treating a number as a list of
+    /// itself wherever it meets a list.
+    #[test]
+    fn test_reference() {
+        #[derive(Clone)]
+        enum Packet {
+            Number(i32),
+            List(Vec<Packet>),
+        }
+
+        fn read(text: &mut &str) -> Packet {
+            if let Some(rest) = text.strip_prefix('[') {
+                *text = rest;
+                let mut items = vec![];
+                while !text.starts_with(']') {
+                    items.push(read(text));
+                    *text = text.strip_prefix(',').unwrap_or(text);
+                }
+                *text = &text[1..];
+                Packet::List(items)
+            } else {
+                let digits = text.find(|ch: char| !ch.is_ascii_digit()).unwrap();
+                let number = text[..digits].parse().unwrap();
+                *text = &text[digits..];
+                Packet::Number(number)
+            }
+        }
+
+        fn compare(left: &Packet, right: &Packet) -> Ordering {
+            let (left, right) = match (left, right) {
+                (Packet::Number(a), Packet::Number(b)) => return a.cmp(b),
+                (Packet::Number(_), Packet::List(b)) => (vec![left.clone()], b.clone()),
+                (Packet::List(a), Packet::Number(_)) => (a.clone(), vec![right.clone()]),
+                (Packet::List(a), Packet::List(b)) => (a.clone(), b.clone()),
+            };
+            for (a, b) in left.iter().zip(&right) {
+                match compare(a, b) {
+                    Ordering::Equal => {}
+                    ordering => return ordering,
+                }
+            }
+            left.len().cmp(&right.len())
+        }
+
+        for seed in 0..20 {
+            let input = generate::generate(seed, 50);
+            let packets: Vec<Packet> = input
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|mut line| read(&mut line))
+                .collect();
+            let tokens = parse_input(&input).unwrap();
+
+            let ordered: usize = (1..)
+                .zip(packets.chunks(2))
+                .filter(|(_, pair)| compare(&pair[0], &pair[1]) == Ordering::Less)
+                .map(|(index, _)| index)
+                .sum();
+            assert_eq!(part1(&tokens), ordered);
+
+            // a packet equal to a divider could go either side of it
+            let dividers = [read(&mut "[[2]]"), read(&mut "[[6]]")];
+            let (packets, tokens): (Vec<_>, Tokens) = packets
+                .into_iter()
+                .zip(tokens)
+                .filter(|(packet, _)| dividers.iter().all(|d| compare(packet, d).is_ne()))
+                .unzip();
+            let before = |divider: &Packet| {
+                let smaller = packets.iter().filter(|p| compare(p, divider).is_lt());
+                smaller.count() + 1
+            };
+            let key = before(&dividers[0]) * (before(&dividers[1]) + 1);
+            assert_eq!(part2(&tokens), key);
+        }
+    }
+}
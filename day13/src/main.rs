@@ -1,14 +1,8 @@
-use day13::*;
+use std::process::ExitCode;
 
-fn main() {
-    let contents = match read_input() {
-        Ok(contents) => contents,
-        Err(error) => panic!("{error}"),
-    };
+use aoc_common::run_day;
+use day13::Day13;
 
-    let parsed_input = parse_input(&contents);
-
-    let answer = get_answer(&parsed_input);
-
-    assert_eq!(answer, CORRECT_ANSWER);
+fn main() -> ExitCode {
+    run_day::<Day13>()
 }
@@ -0,0 +1,57 @@
+use aoc_common::Rng;
+
+use crate::MAX;
+
+/// Rock paths in the puzzle input.
+pub const SIZE: usize = 150;
+
+/// `size` rock paths of up to six horizontal and vertical lines, from row 13
+/// down to about row `13 + size`, but no further than the parser's limit, and
+/// up to 60 columns either side of the sand source at `500,0`.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    (0..size)
+        .map(|_| {
+            let bottom = (13 + size as i64).min(MAX as i64);
+            let (mut x, mut y) = (rng.range(440..=560), rng.range(13..=bottom));
+            let mut path = vec![format!("{x},{y}")];
+            let horizontal = rng.chance(1, 2);
+            for line in 0..rng.range(1..=6) {
+                let length = rng.range(-10..=10);
+                if (line % 2 == 0) == horizontal {
+                    x += length;
+                } else {
+                    y = (y + length).clamp(13, MAX as i64);
+                }
+                path.push(format!("{x},{y}"));
+            }
+            path.join(" -> ") + "\n"
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 20), generate(3, 20));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 20)).unwrap();
+            assert_eq!(tokens.len(), 20);
+            assert!(part1(&tokens) < part2(&tokens));
+        }
+    }
+
+    #[test]
+    fn test_generate_deep() {
+        for size in [MAX as usize, 3 * MAX as usize] {
+            let tokens = parse_input(&generate(7, size)).unwrap();
+            assert_eq!(tokens.len(), size);
+            assert!(tokens.iter().flatten().any(|token| token.line.1 > 900));
+        }
+    }
+}
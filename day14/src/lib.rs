@@ -1,64 +1,160 @@
+pub mod generate;
+
 use core::fmt;
-use std::fs::File;
-use std::io::prelude::*;
-use std::str::FromStr;
+
+use aoc_common::{Grid, Image, Line, Lines, ParseError, Point, Simulation, Solution};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
     line: (i32, i32),
 }
 
-impl FromStr for Token {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
-        let a: Vec<&str> = s.split(',').collect();
-        assert_eq!(a.len(), 2);
-        Ok(Token {
-            line: (a[0].parse().unwrap(), a[1].parse().unwrap()),
-        })
+/// A point of a path as written in the input, like `498,4`.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.line.0, self.line.1)
     }
 }
 
 type Tokens = Vec<Vec<Token>>;
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    Lines::new(14, input)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.all(|line| {
+                let mut path = vec![parse_point(line)?];
+                while line.eat(" -> ") {
+                    path.push(parse_point(line)?);
+                }
+                Ok(path)
+            })
+        })
+        .collect()
+}
+
+/// Largest coordinate accepted, the cave being a grid spanning all of them.
+pub(crate) const MAX: i32 = 1000;
 
-    let mut contents = String::with_capacity(10000);
+fn parse_point(line: &mut Line) -> Result<Token, ParseError> {
+    let x = parse_coordinate(line, "an x coordinate")?;
+    line.literal(",")?;
+    Ok(Token {
+        line: (x, parse_coordinate(line, "a y coordinate")?),
+    })
+}
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
+fn parse_coordinate(line: &mut Line, expected: &str) -> Result<i32, ParseError> {
+    let before = *line;
+    match line.unsigned(expected)? {
+        value if value <= MAX => Ok(value),
+        _ => Err(before.error(&format!("{expected} up to {MAX}"))),
     }
 }
 
-pub fn parse_input(input: &String) -> Tokens {
-    input
-        .split('\n')
-        .filter(|p| !p.is_empty())
-        .map(|f| f.split('>').map(|g| Token::from_str(g).unwrap()).collect())
-        .collect()
+pub fn part1(tokens: &Tokens) -> usize {
+    let mut cave = Cave::build_cave(tokens, false);
+    let mut num = 0;
+    while cave.pour_sand() {
+        num += 1;
+    }
+
+    num
 }
 
-pub fn get_answer(tokens: &Tokens) -> usize {
-    let mut cave = Cave::build_cave(&tokens);
+pub fn part2(tokens: &Tokens) -> usize {
+    let mut cave = Cave::build_cave(tokens, true);
     let mut num = 0;
-    loop {
-        if !cave.pour_sand((cave.sand_origin.0, cave.sand_origin.1)) {
-            break;
-        } else {
-            num += 1;
-        }
+    while cave.pour_sand() {
+        num += 1;
     }
 
     num + 1
 }
 
+/// Part 1 pours sand into the abyss, part 2 onto the floor.
+pub fn simulation(tokens: &Tokens, part: u8) -> Box<dyn Simulation> {
+    Box::new(Sand {
+        cave: Cave::build_cave(tokens, part == 2),
+        grain: None,
+        rested: 0,
+        finished: false,
+    })
+}
+
+/// The cave once all sand has come to rest, rock in grey and sand in yellow.
+pub fn image(tokens: &Tokens, part: u8) -> Image {
+    let mut cave = Cave::build_cave(tokens, part == 2);
+    while cave.pour_sand() {}
+
+    Image::Colour(cave.cave.map(|ch| match ch {
+        '█' => [128, 128, 128],
+        '✺' => [230, 190, 80],
+        _ => [0, 0, 0],
+    }))
+}
+
+/// Sand falling one cell per step, one grain at a time.
+struct Sand {
+    cave: Cave,
+    grain: Option<Point>,
+    /// Grains that came to rest.
+    rested: i64,
+    finished: bool,
+}
+
+impl Simulation for Sand {
+    fn step(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+
+        let position = self.grain.unwrap_or(self.cave.sand_origin);
+        match self.cave.fall(position) {
+            Fall::Moved(next) => self.grain = Some(next),
+            Fall::Rested => {
+                self.grain = None;
+                self.rested += 1;
+            }
+            Fall::Void => self.finished = true,
+        }
+        true
+    }
+
+    fn frame(&self) -> String {
+        let mut cave = self.cave.cave.clone();
+        if let Some(grain) = self.grain {
+            cave[grain] = 'o';
+        }
+
+        cave.to_string()
+    }
+
+    /// The falling grain in the puzzle's coordinates, at the source between
+    /// grains.
+    fn variables(&self) -> Vec<(&'static str, i64)> {
+        let origin = self.cave.sand_origin;
+        let grain = self.grain.unwrap_or(origin);
+        vec![
+            ("grains", self.rested),
+            ("grain_x", (grain.x - origin.x + 500) as i64),
+            ("grain_y", (grain.y - origin.y) as i64),
+        ]
+    }
+}
+
 struct Cave {
-    cave: Vec<Vec<char>>,
-    sand_origin: (usize, usize),
+    cave: Grid<char>,
+    sand_origin: Point,
+    /// Where the last grain poured fell through, which the next one follows
+    /// too up to where it came to rest.
+    path: Vec<Point>,
+}
+
+enum Fall {
+    Moved(Point),
+    Rested,
+    Void,
 }
 
 enum Move {
@@ -69,23 +165,23 @@ enum Move {
     Void,
 }
 
+const DOWN: Point = Point::new(0, 1);
+const DOWN_LEFT: Point = Point::new(-1, 1);
+const DOWN_RIGHT: Point = Point::new(1, 1);
+
 impl Cave {
-    fn where_to_move(&self, position: (usize, usize)) -> Move {
-        if position.0 >= self.cave.len() - 1
-            || position.1 >= self.cave[0].len() - 1
-            || position.1 <= 0
+    fn where_to_move(&self, position: Point) -> Move {
+        if position.y >= self.cave.height() as i32 - 1
+            || position.x >= self.cave.width() as i32 - 1
+            || position.x == 0
         {
             return Move::Void;
         }
 
-        let ch = self.cave[position.0][position.1];
-        let ch_down = self.cave[position.0 + 1][position.1];
-        let ch_downleft = self.cave[position.0 + 1][position.1 - 1];
-        let ch_downright = self.cave[position.0 + 1][position.1 + 1];
+        let ch_down = self.cave[position + DOWN];
+        let ch_downleft = self.cave[position + DOWN_LEFT];
+        let ch_downright = self.cave[position + DOWN_RIGHT];
 
-        if ch == '✺' && ch_down == '✺' && ch_downleft == '✺' && ch_downright == '✺' {
-            return Move::Void;
-        }
         if ch_down == ' ' {
             return Move::Down;
         }
@@ -98,150 +194,121 @@ impl Cave {
             return Move::DownRight;
         }
 
+        // a grain stuck at the source blocks it, by sand or by rock
+        if position == self.sand_origin {
+            return Move::Void;
+        }
         Move::Stay
     }
 
-    fn pour_sand(&mut self, position: (usize, usize)) -> bool {
-        let mut position = position;
+    /// Pours a grain from the source, returning whether it came to rest.
+    fn pour_sand(&mut self) -> bool {
+        let mut position = self.path.pop().unwrap_or(self.sand_origin);
         loop {
-            match self.where_to_move(position) {
-                Move::Void => {
-                    return false;
+            match self.fall(position) {
+                Fall::Moved(next) => {
+                    self.path.push(position);
+                    position = next;
                 }
-                Move::Stay => {
-                    if self.cave[position.0][position.1] == ' ' {
-                        self.cave[position.0][position.1] = '✺';
-                    }
-                    return true;
-                }
-                Move::Down => position.0 += 1,
-                Move::DownLeft => position = (position.0 + 1, position.1 - 1),
-                Move::DownRight => position = (position.0 + 1, position.1 + 1),
+                Fall::Rested => return true,
+                Fall::Void => return false,
             }
         }
     }
 
-    fn build_cave(tokens: &Tokens) -> Cave {
-        let (i_min, i_max, j_min, j_max) = {
-            let mut i_min: Option<i32> = None;
-            let mut i_max: Option<i32> = None;
-            let mut j_min: Option<i32> = None;
-            let mut j_max: Option<i32> = None;
-
-            for arr in tokens {
-                for Token { line } in arr {
-                    let (i, j) = *line;
-
-                    if i_min == None || i_min > Some(i) {
-                        i_min = Some(i);
-                    }
-                    if i_max == None || i_max < Some(i) {
-                        i_max = Some(i);
-                    }
-
-                    if j_min == None || j_min > Some(j) {
-                        j_min = Some(j);
-                    }
-                    if j_max == None || j_max < Some(j) {
-                        j_max = Some(j);
-                    }
+    /// Moves a grain of sand at `position` by one cell, or lets it come to rest.
+    fn fall(&mut self, position: Point) -> Fall {
+        match self.where_to_move(position) {
+            Move::Void => Fall::Void,
+            Move::Stay => {
+                if self.cave[position] == ' ' {
+                    self.cave[position] = '✺';
                 }
+                Fall::Rested
             }
+            Move::Down => Fall::Moved(position + DOWN),
+            Move::DownLeft => Fall::Moved(position + DOWN_LEFT),
+            Move::DownRight => Fall::Moved(position + DOWN_RIGHT),
+        }
+    }
 
-            if j_min > Some(0) {
-                j_min = Some(0);
-            }
-
-            assert_ne!(i_min, None);
-            assert_ne!(i_max, None);
-            assert_ne!(j_min, None);
-            assert_ne!(j_max, None);
-
-            (
-                i_min.unwrap(),
-                i_max.unwrap(),
-                j_min.unwrap(),
-                j_max.unwrap(),
-            )
-        };
-        let mut cave = Vec::new();
-        for _ in 0..=(j_max - j_min) {
-            let mut tmp = Vec::new();
-            for _ in 0..=(i_max - i_min) {
-                tmp.push(' ');
-            }
-            cave.push(tmp.clone());
+    /// With `floor`, an infinite floor two rows below the lowest rock is added,
+    /// wide enough that no sand can fall past its ends.
+    fn build_cave(tokens: &Tokens, floor: bool) -> Cave {
+        let mut tokens = tokens.clone();
+        if floor {
+            let floor = tokens.iter().flatten().map(|t| t.line.1).max().unwrap_or(0) + 2;
+            tokens.push(Vec::from([
+                Token {
+                    line: (500 - floor - 1, floor),
+                },
+                Token {
+                    line: (500 + floor + 1, floor),
+                },
+            ]));
         }
 
-        for wall in tokens {
-            add_walls(&mut cave, &wall, i_min);
+        let points = || tokens.iter().flatten().map(|t| t.line);
+        // the sand origin at 500,0 is always inside the cave
+        let x_min = points().map(|p| p.0).chain([500]).min().unwrap();
+        let x_max = points().map(|p| p.0).chain([500]).max().unwrap();
+        let y_max = points().map(|p| p.1).chain([0]).max().unwrap();
+
+        let width = (x_max - x_min + 1) as usize;
+        let mut cave = Grid::new(width, y_max as usize + 1, ' ');
+
+        for wall in &tokens {
+            add_walls(&mut cave, wall, x_min);
         }
 
-        let sand_origin = (0, 500 - (i_min as usize));
-        cave[0][500 - (i_min as usize)] = '✺';
+        let sand_origin = Point::new(500 - x_min, 0);
+        cave[sand_origin] = '✺';
 
-        Self { cave, sand_origin }
+        Self {
+            cave,
+            sand_origin,
+            path: Vec::new(),
+        }
     }
 }
 
-fn add_walls(cave: &mut Vec<Vec<char>>, wall: &Vec<Token>, i_min: i32) {
-    let mut window = wall.windows(2);
-    loop {
-        match window.next() {
-            Some(w) => {
-                let p0 = (w[0].line.0 - i_min, w[0].line.1);
-                let p1 = (w[1].line.0 - i_min, w[1].line.1);
-
-                let i_min = {
-                    if p0.0 < p1.0 {
-                        p0.0
-                    } else {
-                        p1.0
-                    }
-                };
-                let i_max = {
-                    if p0.0 > p1.0 {
-                        p0.0
-                    } else {
-                        p1.0
-                    }
-                };
-                let j_min = {
-                    if p0.1 < p1.1 {
-                        p0.1
-                    } else {
-                        p1.1
-                    }
-                };
-                let j_max = {
-                    if p0.1 > p1.1 {
-                        p0.1
-                    } else {
-                        p1.1
-                    }
-                };
+fn add_walls(cave: &mut Grid<char>, wall: &[Token], x_min: i32) {
+    for w in wall.windows(2) {
+        let (p0, p1) = (w[0].line, w[1].line);
 
-                for j in j_min..j_max + 1 {
-                    for i in i_min..i_max + 1 {
-                        cave[j as usize][i as usize] = '█';
-                    }
-                }
+        for y in p0.1.min(p1.1)..=p0.1.max(p1.1) {
+            for x in p0.0.min(p1.0)..=p0.0.max(p1.0) {
+                cave[Point::new(x - x_min, y)] = '█';
             }
-            None => return,
         }
     }
 }
 
-impl std::fmt::Display for Cave {
+impl fmt::Display for Cave {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut cave = String::new();
-        for row in &self.cave {
-            for ch in row {
-                cave.push(*ch);
-            }
-            cave.push('\n');
-        }
-        write!(f, "{}", cave)
+        write!(f, "{}", self.cave)
+    }
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Tokens;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
 }
 
@@ -253,6 +320,128 @@ mod tests {
     fn test_build_resursion_1() {
         assert_eq!(1, 1);
     }
-}
 
-pub const CORRECT_ANSWER: usize = 22499;
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("498,4 -> 498,6\n503,4 -> 502;4\n"),
+            Err(ParseError::new(14, 2, 13, ";", "`,`"))
+        );
+        assert_eq!(
+            parse_input("498,4 -> 498,4000\n"),
+            Err(ParseError::new(
+                14,
+                1,
+                14,
+                "4000",
+                "a y coordinate up to 1000"
+            ))
+        );
+        assert_eq!(
+            parse_input("498,4>498,6\n"),
+            Err(ParseError::new(14, 1, 6, ">", "the end of the line"))
+        );
+    }
+
+    #[test]
+    fn test_blocked_source() {
+        let tokens = parse_input("499,1 -> 501,1\n").unwrap();
+        assert_eq!((part1(&tokens), part2(&tokens)), (0, 1));
+    }
+
+    #[test]
+    fn test_debug() {
+        use aoc_common::{DebugCommand, Debugger};
+
+        let mut sand = simulation(&parse_input(EXAMPLE).unwrap(), 1);
+        let mut debugger = Debugger::new(sand.as_mut());
+        let until = DebugCommand::parse("until grains == 1").unwrap();
+        debugger.execute(&until).unwrap();
+        assert!(debugger.variables().contains(&("grain_x", 500)));
+        debugger
+            .execute(&DebugCommand::parse("s 8").unwrap())
+            .unwrap();
+        assert!(debugger.variables().contains(&("grain_y", 8)));
+        debugger.execute(&DebugCommand::Run).unwrap();
+        assert!(debugger.variables().contains(&("grains", 24)));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 24);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 93);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day14>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text: String = tokens
+                .iter()
+                .map(|path| {
+                    let points: Vec<String> = path.iter().map(Token::to_string).collect();
+                    points.join(" -> ") + "\n"
+                })
+                .collect();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Drops sand through a set of blocked points instead of a grid.
+    #[test]
+    fn test_reference() {
+        use std::collections::HashSet;
+
+        let pour = |rock: &HashSet<(i32, i32)>, floor: bool| {
+            let bottom = rock.iter().map(|(_, y)| *y).max().unwrap();
+            let mut blocked = rock.clone();
+            let mut grains = 0;
+            while !blocked.contains(&(500, 0)) {
+                let (mut x, mut y) = (500, 0);
+                loop {
+                    if y > bottom && !floor {
+                        return grains;
+                    }
+                    let below = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)];
+                    match below.into_iter().find(|point| !blocked.contains(point)) {
+                        Some(point) if y + 1 < bottom + 2 => (x, y) = point,
+                        _ => break,
+                    }
+                }
+                blocked.insert((x, y));
+                grains += 1;
+            }
+            grains
+        };
+
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 5)).unwrap();
+            let mut rock = HashSet::new();
+            for path in &tokens {
+                for pair in path.windows(2) {
+                    let ((x1, y1), (x2, y2)) = (pair[0].line, pair[1].line);
+                    for x in x1.min(x2)..=x1.max(x2) {
+                        for y in y1.min(y2)..=y1.max(y2) {
+                            rock.insert((x, y));
+                        }
+                    }
+                }
+            }
+            assert_eq!(
+                (part1(&tokens), part2(&tokens)),
+                (pour(&rock, false), pour(&rock, true))
+            );
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use aoc_common::Rng;
+
+use crate::Token;
+
+/// Sensors in the puzzle input.
+pub const SIZE: usize = 30;
+
+/// Largest coordinate of the area part 2 searches.
+const MAX: i64 = 4000000;
+
+/// Reports from about `size` sensors covering all of part 2's search area
+/// except the one position of the distress beacon.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    reports(&mut rng, size)
+        .1
+        .iter()
+        .map(|Token { sensor, beacon }| {
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                sensor.0, sensor.1, beacon.0, beacon.1
+            )
+        })
+        .collect()
+}
+
+/// The distress beacon and the reports hiding it.
+///
+/// Sensors reaching `reach` on a lattice where their reach tiles the plane
+/// cover the search area, each one reaching just short of the distress
+/// beacon. The sensors that had to be cut short are all close to it, and
+/// four sensors diagonally around it cover what they leave uncovered.
+fn reports(rng: &mut Rng, size: usize) -> ((i64, i64), Vec<Token>) {
+    let hidden = (rng.range(0..=MAX), rng.range(0..=MAX));
+    let manhattan = |(x, y): (i64, i64)| (x - hidden.0).abs() + (y - hidden.1).abs();
+
+    // about (MAX / reach + 2)^2 / 2 sensors touch the search area
+    let across = (((2 * size) as f64).sqrt() as i64 - 2).max(1);
+    let reach = MAX / across + 1;
+    let jitter = reach / 8;
+    let offset = (rng.range(0..=reach), rng.range(0..=reach));
+
+    let mut reports = Vec::new();
+    let mut report = |rng: &mut Rng, sensor: (i64, i64), distance: i64| {
+        let dx = rng.range(-distance..=distance);
+        let dy = (distance - dx.abs()) * if rng.chance(1, 2) { 1 } else { -1 };
+        let beacon = (sensor.0 + dx, sensor.1 + dy);
+        reports.push(Token { sensor, beacon });
+    };
+
+    let span = MAX / reach + 4;
+    for m in -span..=span {
+        for n in -span..=span {
+            let sensor = (reach * (m + n) + offset.0, reach * (m - n) + offset.1);
+            let outside = |value: i64| (-value).max(value - MAX).max(0);
+            if outside(sensor.0) + outside(sensor.1) > reach + jitter {
+                continue;
+            }
+            let distance = (reach + rng.range(0..=jitter)).min(manhattan(sensor) - 1);
+            if distance >= 0 {
+                report(rng, sensor, distance);
+            }
+        }
+    }
+
+    let diagonal = reach + jitter + 1;
+    for (dx, dy) in [(-1, -1), (1, -1), (1, 1), (-1, 1)] {
+        let sensor = (hidden.0 + dx * diagonal, hidden.1 + dy * diagonal);
+        report(rng, sensor, 2 * diagonal - 1);
+    }
+
+    (hidden, reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1};
+
+    fn covered(reports: &[Token], (x, y): (i64, i64)) -> bool {
+        reports.iter().any(|Token { sensor, beacon }| {
+            let reach = (sensor.0 - beacon.0).abs() + (sensor.1 - beacon.1).abs();
+            (sensor.0 - x).abs() + (sensor.1 - y).abs() <= reach
+        })
+    }
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, SIZE), generate(3, SIZE));
+        for (seed, size) in (0..10).zip([1, 5, 10, 30, 100].into_iter().cycle()) {
+            let input = generate(seed, size);
+            let tokens = parse_input(&input).unwrap();
+            assert!(part1(&tokens) > 0);
+
+            let mut rng = Rng::new(seed);
+            let (hidden, reports) = reports(&mut rng, size);
+            assert_eq!(reports, tokens);
+            assert!(!covered(&reports, hidden));
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let point = (hidden.0 + dx, hidden.1 + dy);
+                    assert!(point == hidden || covered(&reports, point));
+                }
+            }
+            let mut rng = Rng::new(seed + 100);
+            for _ in 0..1000 {
+                let point = (rng.range(0..=MAX), rng.range(0..=MAX));
+                assert!(point == hidden || covered(&reports, point));
+            }
+        }
+    }
+}
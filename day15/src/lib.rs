@@ -1,6 +1,8 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::str::FromStr;
+pub mod generate;
+
+use std::fmt;
+
+use aoc_common::{Line, Lines, ParseError, Solution};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Token {
@@ -8,77 +10,141 @@ pub struct Token {
     beacon: (i64, i64),
 }
 
-impl FromStr for Token {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, <Self as FromStr>::Err> {
-        let a: Vec<&str> = s.split(' ').filter(|p| p.contains('=')).collect();
-        let mut values = Vec::new();
-        for s in a {
-            let tmp = s.split('=').collect::<Vec<&str>>();
-            let mut last = String::from(*tmp.last().unwrap());
-            if !last.chars().last().unwrap().is_ascii_digit() {
-                last.remove(last.len() - 1);
-            }
-            values.push(last.parse::<i64>().unwrap());
-        }
-
-        assert_eq!(values.len(), 4);
-
-        Ok(Token {
-            sensor: (values[0], values[1]),
-            beacon: (values[2], values[3]),
-        })
+/// The report as written in the input.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ((sx, sy), (bx, by)) = (self.sensor, self.beacon);
+        write!(
+            f,
+            "Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}"
+        )
     }
 }
 
 type Tokens = Vec<Token>;
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+/// Size of the square part 2 searches.
+const MAX: i64 = 4000000;
 
-    let mut contents = String::with_capacity(10000);
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    Lines::new(15, input)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.all(|line| {
+                line.literal("Sensor at ")?;
+                let sensor = parse_position(line)?;
+                line.literal(": closest beacon is at ")?;
+                let beacon = parse_position(line)?;
+                Ok(Token { sensor, beacon })
+            })
+        })
+        .collect()
+}
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
-    }
+/// Parses `x=X, y=Y`.
+fn parse_position(line: &mut Line) -> Result<(i64, i64), ParseError> {
+    line.literal("x=")?;
+    let x = line.signed("a coordinate")?;
+    line.literal(", y=")?;
+    Ok((x, line.signed("a coordinate")?))
 }
 
-pub fn parse_input(input: &String) -> Tokens {
-    input
-        .split('\n')
-        .filter(|p| !p.is_empty())
-        .map(|g| Token::from_str(g).unwrap())
-        .collect()
+pub fn part1(tokens: &Tokens) -> i64 {
+    no_beacon_positions(tokens, 2000000)
 }
 
-pub fn get_answer(_tokens: &Tokens) -> i64 {
-    let map = Map::from(_tokens);
+/// `None` when the sensors reach every position in the square.
+pub fn part2(tokens: &Tokens) -> Option<i64> {
+    tuning_frequency(tokens, MAX)
+}
 
-    let mut all_outer_edge_points = Vec::with_capacity(94476656);
+/// Number of positions in `row` where a beacon cannot be present.
+fn no_beacon_positions(tokens: &Tokens, row: i64) -> i64 {
+    let mut ranges: Vec<(i64, i64)> = Map::from(tokens)
+        .sensors
+        .iter()
+        .filter_map(|sensor| {
+            let reach = sensor.manhattan - (sensor.point.y - row).abs();
+            if reach < 0 {
+                None
+            } else {
+                Some((sensor.point.x - reach, sensor.point.x + reach))
+            }
+        })
+        .collect();
+    ranges.sort();
 
-    for sensor in &map.sensors {
-        for point in sensor.outer_edge() {
-            all_outer_edge_points.push(point);
+    let mut covered = 0;
+    let mut end = i64::MIN;
+    for (from, to) in ranges {
+        let from = from.max(end + 1);
+        if to >= from {
+            covered += to - from + 1;
+            end = to;
         }
     }
 
-    'outer: for point in all_outer_edge_points {
-        if point.x < 0 || point.y < 0 || point.x > 4000000 || point.y > 4000000 {
-            continue 'outer;
-        }
+    let mut beacons: Vec<i64> = tokens
+        .iter()
+        .filter(|t| t.beacon.1 == row)
+        .map(|t| t.beacon.0)
+        .collect();
+    beacons.sort();
+    beacons.dedup();
+
+    covered - beacons.len() as i64
+}
+
+/// Tuning frequency of a position in the square from 0 to `max` no sensor
+/// reaches, `None` when there is none.
+///
+/// Such a position is next to the edges of sensors' reach or of the square,
+/// so only the positions where those edges cross, or nearly cross, are
+/// checked.
+fn tuning_frequency(tokens: &Tokens, max: i64) -> Option<i64> {
+    let map = Map::from(tokens);
+
+    // the lines `x + y = sum` and `x - y = difference` just out of reach
+    let mut sums = Vec::new();
+    let mut differences = Vec::new();
+    for sensor in &map.sensors {
+        let (point, reach) = (&sensor.point, sensor.manhattan + 1);
+        sums.extend([point.x + point.y - reach, point.x + point.y + reach]);
+        differences.extend([point.x - point.y - reach, point.x - point.y + reach]);
+    }
 
-        for sensor in &map.sensors {
-            if sensor.inside_manhattan(point.clone()) {
-                continue 'outer;
+    let mut candidates = vec![(0, 0), (0, max), (max, 0), (max, max)];
+    for sum in &sums {
+        for difference in &differences {
+            // lines crossing between positions are rounded both ways
+            let (x, y) = (sum + difference, sum - difference);
+            for x in [x.div_euclid(2), (x + 1).div_euclid(2)] {
+                for y in [y.div_euclid(2), (y + 1).div_euclid(2)] {
+                    candidates.push((x, y));
+                }
             }
         }
-        return point.x * 4000000 + point.y;
+        candidates.extend([(0, *sum), (*sum, 0), (max, sum - max), (sum - max, max)]);
+    }
+    for difference in &differences {
+        candidates.extend([
+            (0, -difference),
+            (*difference, 0),
+            (max, max - difference),
+            (difference + max, max),
+        ]);
     }
 
-    unreachable!()
+    candidates
+        .into_iter()
+        .map(|(x, y)| Point { x, y })
+        .filter(|point| (0..=max).contains(&point.x) && (0..=max).contains(&point.y))
+        .find(|point| {
+            map.sensors
+                .iter()
+                .all(|sensor| !sensor.inside_manhattan(point.clone()))
+        })
+        .map(|point| point.x * 4000000 + point.y)
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -94,45 +160,10 @@ struct Sensor {
 }
 
 impl Sensor {
-    fn outer_edge(&self) -> Vec<Point> {
-        let manhattan = self.manhattan + 1;
-
-        let mut points = Vec::new();
-
-        for row in self.point.y..(self.point.y + manhattan + 1) {
-            let n_chars = 2 * manhattan + 1 - (row - self.point.y) * 2;
-            let left = self.point.x - n_chars / 2;
-            let right = self.point.x + n_chars / 2;
-
-            points.push(Point { x: left, y: row });
-            if row != self.point.y {
-                points.push(Point {
-                    x: left,
-                    y: self.point.y - (row - self.point.y),
-                })
-            };
-            if left != right {
-                points.push(Point { x: right, y: row });
-                if row != self.point.y {
-                    points.push(Point {
-                        x: right,
-                        y: self.point.y - (row - self.point.y),
-                    });
-                }
-            }
-        }
-
-        points
-    }
-
     fn inside_manhattan(&self, point: Point) -> bool {
         let manhattan_to_point = (self.point.x - point.x).abs() + (self.point.y - point.y).abs();
 
-        if manhattan_to_point > self.manhattan {
-            return false;
-        } else {
-            return true;
-        }
+        manhattan_to_point <= self.manhattan
     }
 }
 
@@ -164,4 +195,116 @@ impl Map {
     }
 }
 
-pub const CORRECT_ANSWER: i64 = 12274327017867;
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Tokens;
+    type Output1 = i64;
+    type Output2 = Option<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        let input = EXAMPLE.replace("x=13, y=2", "x=13, y=two");
+        assert_eq!(
+            parse_input(&input),
+            Err(ParseError::new(15, 3, 19, "two", "a coordinate"))
+        );
+        assert_eq!(
+            parse_input("Sensor at x=2, y=18\n"),
+            Err(ParseError::new(15, 1, 20, "", "`: closest beacon is at `"))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(no_beacon_positions(&parse_input(EXAMPLE).unwrap(), 10), 26);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(
+            tuning_frequency(&parse_input(EXAMPLE).unwrap(), 20),
+            Some(56000011)
+        );
+        let everywhere = "Sensor at x=0, y=0: closest beacon is at x=8000000, y=0\n";
+        let tokens = parse_input(everywhere).unwrap();
+        assert_eq!(part1(&tokens), 12000001);
+        assert_eq!(part2(&tokens), None);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day15>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text: String = tokens.iter().map(|token| format!("{token}\n")).collect();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Checks every position of a small cave against every sensor.
+    #[test]
+    fn test_reference() {
+        const MAX: i64 = 20;
+
+        let mut rng = aoc_common::Rng::new(15);
+        for _ in 0..200 {
+            let tokens: Tokens = (0..rng.range(1..=6))
+                .map(|_| {
+                    let sensor = (rng.range(0..=MAX), rng.range(0..=MAX));
+                    let beacon = (sensor.0 + rng.range(-8..=8), sensor.1 + rng.range(-8..=8));
+                    Token { sensor, beacon }
+                })
+                .collect();
+            let covered = |(x, y): (i64, i64)| {
+                tokens.iter().any(|Token { sensor, beacon }| {
+                    let reach = (sensor.0 - beacon.0).abs() + (sensor.1 - beacon.1).abs();
+                    (sensor.0 - x).abs() + (sensor.1 - y).abs() <= reach
+                })
+            };
+
+            let row = rng.range(0..=MAX);
+            let no_beacon = (-MAX * 2..=MAX * 3)
+                .filter(|x| covered((*x, row)))
+                .filter(|x| tokens.iter().all(|token| token.beacon != (*x, row)))
+                .count();
+            assert_eq!(no_beacon_positions(&tokens, row), no_beacon as i64);
+
+            let uncovered = (0..=MAX)
+                .flat_map(|x| (0..=MAX).map(move |y| (x, y)))
+                .any(|point| !covered(point));
+            let frequency = tuning_frequency(&tokens, MAX);
+            assert_eq!(frequency.is_some(), uncovered);
+            if let Some(frequency) = frequency {
+                let point = (frequency / 4000000, frequency % 4000000);
+                assert!((0..=MAX).contains(&point.0) && (0..=MAX).contains(&point.1));
+                assert!(!covered(point), "{point:?} is covered");
+            }
+        }
+    }
+}
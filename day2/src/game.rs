@@ -0,0 +1,224 @@
+use crate::Outcome;
+
+/// One of a game's moves and what playing it scores.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Move<'a> {
+    pub name: &'a str,
+    pub score: i32,
+}
+
+/// A cyclic game like rock-paper-scissors. Moves are numbered by their index
+/// in `moves`, and each beats the half of the others just before it in the
+/// cycle: with rock, paper and scissors, paper beats rock and rock beats
+/// scissors.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Game<'a> {
+    moves: &'a [Move<'a>],
+    loss: i32,
+    draw: i32,
+    win: i32,
+}
+
+/// The puzzle's game.
+pub const RPS: Game<'static> = match Game::new(
+    &[
+        Move {
+            name: "rock",
+            score: 1,
+        },
+        Move {
+            name: "paper",
+            score: 2,
+        },
+        Move {
+            name: "scissors",
+            score: 3,
+        },
+    ],
+    0,
+    3,
+    6,
+) {
+    Ok(game) => game,
+    Err(error) => panic!("{}", error),
+};
+
+/// Rock-paper-scissors-lizard-Spock, ordered so that each move beats the two
+/// before it.
+pub const RPSLS: Game<'static> = match Game::new(
+    &[
+        Move {
+            name: "rock",
+            score: 1,
+        },
+        Move {
+            name: "Spock",
+            score: 2,
+        },
+        Move {
+            name: "paper",
+            score: 3,
+        },
+        Move {
+            name: "lizard",
+            score: 4,
+        },
+        Move {
+            name: "scissors",
+            score: 5,
+        },
+    ],
+    0,
+    3,
+    6,
+) {
+    Ok(game) => game,
+    Err(error) => panic!("{}", error),
+};
+
+impl<'a> Game<'a> {
+    /// Fails unless there is an odd number of moves, without which some pair
+    /// would beat each other or neither would.
+    pub const fn new(
+        moves: &'a [Move<'a>],
+        loss: i32,
+        draw: i32,
+        win: i32,
+    ) -> Result<Self, &'static str> {
+        if moves.len().is_multiple_of(2) {
+            return Err("a cyclic game needs an odd number of moves");
+        }
+        Ok(Self {
+            moves,
+            loss,
+            draw,
+            win,
+        })
+    }
+
+    pub fn moves(&self) -> &'a [Move<'a>] {
+        self.moves
+    }
+
+    /// The index of the move called `name`.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.moves.iter().position(|m| m.name == name)
+    }
+
+    /// How the round ends for whoever plays `you` against `opponent`.
+    pub fn outcome(&self, opponent: usize, you: usize) -> Outcome {
+        let n = self.moves.len();
+        match (you + n - opponent) % n {
+            0 => Outcome::DRAW,
+            ahead if ahead <= n / 2 => Outcome::WIN,
+            _ => Outcome::LOSS,
+        }
+    }
+
+    /// A move ending the round against `opponent` with `outcome`, the
+    /// nearest one in the cycle when there are several.
+    pub fn respond(&self, opponent: usize, outcome: Outcome) -> usize {
+        let n = self.moves.len();
+        match outcome {
+            Outcome::DRAW => opponent,
+            Outcome::WIN => (opponent + 1) % n,
+            Outcome::LOSS => (opponent + n - 1) % n,
+        }
+    }
+
+    /// What playing `you` scores when the round ends with `outcome`.
+    pub fn score(&self, you: usize, outcome: Outcome) -> i32 {
+        let outcome = match outcome {
+            Outcome::LOSS => self.loss,
+            Outcome::DRAW => self.draw,
+            Outcome::WIN => self.win,
+        };
+        self.moves[you].score + outcome
+    }
+
+    /// Total scores of both players over rounds of `(first, second)` moves.
+    pub fn tournament(&self, rounds: &[(usize, usize)]) -> (i32, i32) {
+        rounds.iter().fold((0, 0), |(first, second), (a, b)| {
+            (
+                first + self.score(*a, self.outcome(*b, *a)),
+                second + self.score(*b, self.outcome(*a, *b)),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Who beats whom in rock-paper-scissors-lizard-Spock.
+    const RULES: [(&str, &str); 10] = [
+        ("scissors", "paper"),
+        ("paper", "rock"),
+        ("rock", "lizard"),
+        ("lizard", "Spock"),
+        ("Spock", "scissors"),
+        ("scissors", "lizard"),
+        ("lizard", "paper"),
+        ("paper", "Spock"),
+        ("Spock", "rock"),
+        ("rock", "scissors"),
+    ];
+
+    #[test]
+    fn test_rps() {
+        let [rock, paper, scissors] = ["rock", "paper", "scissors"].map(|m| RPS.find(m).unwrap());
+        assert_eq!(RPS.outcome(rock, paper), Outcome::WIN);
+        assert_eq!(RPS.outcome(rock, scissors), Outcome::LOSS);
+        assert_eq!(RPS.outcome(scissors, scissors), Outcome::DRAW);
+        assert_eq!(RPS.respond(scissors, Outcome::WIN), rock);
+        assert_eq!(RPS.score(paper, Outcome::WIN), 8);
+        assert_eq!(RPS.find("lizard"), None);
+    }
+
+    #[test]
+    fn test_rpsls() {
+        let n = RPSLS.moves().len();
+        for a in 0..n {
+            for b in 0..n {
+                let name = |i: usize| RPSLS.moves()[i].name;
+                let expected = if a == b {
+                    Outcome::DRAW
+                } else if RULES.contains(&(name(a), name(b))) {
+                    Outcome::WIN
+                } else {
+                    Outcome::LOSS
+                };
+                assert_eq!(
+                    RPSLS.outcome(b, a),
+                    expected,
+                    "{} against {}",
+                    name(a),
+                    name(b)
+                );
+            }
+            for outcome in [Outcome::LOSS, Outcome::DRAW, Outcome::WIN] {
+                assert_eq!(RPSLS.outcome(a, RPSLS.respond(a, outcome)), outcome);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tournament() {
+        let [rock, spock, lizard] = ["rock", "Spock", "lizard"].map(|m| RPSLS.find(m).unwrap());
+        let rounds = [(rock, spock), (lizard, spock), (rock, rock)];
+        assert_eq!(
+            RPSLS.tournament(&rounds),
+            (1 + (4 + 6) + (1 + 3), (2 + 6) + 2 + (1 + 3))
+        );
+    }
+
+    #[test]
+    fn test_even() {
+        assert_eq!(
+            Game::new(&RPS.moves()[..2], 0, 3, 6),
+            Err("a cyclic game needs an odd number of moves")
+        );
+        assert_eq!(Game::new(RPS.moves(), 0, 3, 6), Ok(RPS));
+    }
+}
@@ -0,0 +1,37 @@
+use aoc_common::Rng;
+
+/// Rounds in the puzzle input.
+pub const SIZE: usize = 2500;
+
+/// A strategy guide of `size` rounds.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                rng.pick(&['A', 'B', 'C']),
+                rng.pick(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 20), generate(3, 20));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 20)).unwrap();
+            assert_eq!(tokens.len(), 20);
+            // every round scores between 1 and 9 points
+            assert!((20..=180).contains(&part1(&tokens)));
+            assert!((20..=180).contains(&part2(&tokens)));
+        }
+    }
+}
@@ -1,142 +1,229 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::str::FromStr;
-
-const DRAW: i32 = 3;
-const WIN: i32 = 6;
-const LOSS: i32 = 0;
-const ROCK: i32 = 1;
-const PAPER: i32 = 2;
-const SCISSOR: i32 = 3;
-
-#[derive(Debug, PartialEq)]
+pub mod game;
+pub mod generate;
+
+use std::fmt;
+
+use aoc_common::{Lines, ParseError, Solution};
+use game::RPS;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Outcome {
     DRAW,
     WIN,
     LOSS,
 }
 
-impl Outcome {
-    fn value(self: &Self) -> i32 {
-        match *self {
-            Self::DRAW => DRAW,
-            Self::WIN => WIN,
-            Self::LOSS => LOSS,
-        }
-    }
+/// A shape of [`RPS`], by its index there.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Pick {
+    ROCK,
+    PAPER,
+    SCISSOR,
+}
 
-    fn calculate(opponent: &Pick, you: &Pick) -> Self {
-        match opponent {
-            Pick::ROCK => match you {
-                Pick::ROCK => Self::DRAW,
-                Pick::PAPER => Self::WIN,
-                Pick::SCISSOR => Self::LOSS,
-            },
-            Pick::PAPER => match you {
-                Pick::ROCK => Self::LOSS,
-                Pick::PAPER => Self::DRAW,
-                Pick::SCISSOR => Self::WIN,
-            },
-            Pick::SCISSOR => match you {
-                Pick::ROCK => Self::WIN,
-                Pick::PAPER => Self::LOSS,
-                Pick::SCISSOR => Self::DRAW,
-            },
-        }
+impl Pick {
+    const ALL: [Self; 3] = [Self::ROCK, Self::PAPER, Self::SCISSOR];
+}
+
+/// The opponent's column: `A`, `B` or `C`.
+impl fmt::Display for Pick {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = match self {
+            Self::ROCK => "A",
+            Self::PAPER => "B",
+            Self::SCISSOR => "C",
+        };
+        write!(f, "{column}")
     }
 }
 
-impl FromStr for Outcome {
-    type Err = ();
+/// The second column of the strategy guide, whose meaning is up to the
+/// [`Interpretation`] it is scored with.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Column {
+    X,
+    Y,
+    Z,
+}
 
-    fn from_str(input: &str) -> Result<Outcome, ()> {
-        match input {
-            "Y" => Ok(Outcome::DRAW),
-            "Z" => Ok(Outcome::WIN),
-            "X" => Ok(Outcome::LOSS),
-            _ => panic!("Pick cant be parsed!"),
-        }
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum Pick {
-    ROCK,
-    PAPER,
-    SCISSOR,
+/// What the second column tells you to do, each variant mapping `X`, `Y`
+/// and `Z` in that order.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Interpretation {
+    /// The shape to play.
+    Move([Pick; 3]),
+    /// How the round must end.
+    Outcome([Outcome; 3]),
 }
 
-impl Pick {
-    fn value(self: &Self) -> i32 {
-        match *self {
-            Self::ROCK => ROCK,
-            Self::PAPER => PAPER,
-            Self::SCISSOR => SCISSOR,
+impl Interpretation {
+    /// Part 1's guess: `X` for rock, `Y` for paper, `Z` for scissors.
+    pub const MOVES: Self = Self::Move([Pick::ROCK, Pick::PAPER, Pick::SCISSOR]);
+    /// Part 2's reading: `X` to lose, `Y` to draw, `Z` to win.
+    pub const OUTCOMES: Self = Self::Outcome([Outcome::LOSS, Outcome::DRAW, Outcome::WIN]);
+
+    /// Your shape and the outcome of a round against `opponent`.
+    pub fn round(&self, opponent: Pick, column: Column) -> (Pick, Outcome) {
+        match self {
+            Self::Move(picks) => {
+                let you = picks[column as usize];
+                (you, RPS.outcome(opponent as usize, you as usize))
+            }
+            Self::Outcome(outcomes) => {
+                let outcome = outcomes[column as usize];
+                (Pick::ALL[RPS.respond(opponent as usize, outcome)], outcome)
+            }
         }
     }
 
-    fn calculate(opponent: &Self, outcome: &Outcome) -> Self {
-        match opponent {
-            Pick::ROCK => match outcome {
-                Outcome::DRAW => Self::ROCK,
-                Outcome::WIN => Self::PAPER,
-                Outcome::LOSS => Self::SCISSOR,
-            },
-            Pick::PAPER => match outcome {
-                Outcome::DRAW => Self::PAPER,
-                Outcome::WIN => Self::SCISSOR,
-                Outcome::LOSS => Self::ROCK,
-            },
-            Pick::SCISSOR => match outcome {
-                Outcome::DRAW => Self::SCISSOR,
-                Outcome::WIN => Self::ROCK,
-                Outcome::LOSS => Self::PAPER,
-            },
-        }
+    /// Total score of following the guide read this way.
+    pub fn score(&self, tokens: &Tokens) -> i32 {
+        tokens
+            .iter()
+            .map(|(opponent, column)| {
+                let (you, outcome) = self.round(*opponent, *column);
+                RPS.score(you as usize, outcome)
+            })
+            .sum()
     }
 }
 
-impl FromStr for Pick {
-    type Err = ();
+type Tokens = Vec<(Pick, Column)>;
 
-    fn from_str(input: &str) -> Result<Pick, ()> {
-        match input {
-            "A" => Ok(Pick::ROCK),
-            "B" => Ok(Pick::PAPER),
-            "C" => Ok(Pick::SCISSOR),
-            _ => panic!("Pick cant be parsed!"),
-        }
-    }
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let picks = [("A", Pick::ROCK), ("B", Pick::PAPER), ("C", Pick::SCISSOR)];
+    let columns = [("X", Column::X), ("Y", Column::Y), ("Z", Column::Z)];
+
+    Lines::new(2, input).parse_each(|line| {
+        let opponent = line.one_of(&picks, "A, B or C")?;
+        line.literal(" ")?;
+        let column = line.one_of(&columns, "X, Y or Z")?;
+        Ok((opponent, column))
+    })
+}
+
+pub fn part1(tokens: &Tokens) -> i32 {
+    Interpretation::MOVES.score(tokens)
 }
 
-type Tokens = Vec<(Pick, Outcome)>;
+pub fn part2(tokens: &Tokens) -> i32 {
+    Interpretation::OUTCOMES.score(tokens)
+}
 
-pub fn parse_input(mut file: File) -> Tokens {
-    let mut contents = String::with_capacity(10000);
-    file.read_to_string(&mut contents).unwrap();
+pub struct Day2;
 
-    let mut parsed_input = Tokens::new();
-    for line in contents.lines() {
-        let split: Vec<&str> = line.split_whitespace().collect();
-        assert_eq!(split.len(), 2);
+impl Solution for Day2 {
+    const DAY: u8 = 2;
 
-        let opponent = Pick::from_str(split[0]).unwrap();
-        let outcome = Outcome::from_str(split[1]).unwrap();
+    type Input = Tokens;
+    type Output1 = i32;
+    type Output2 = i32;
 
-        parsed_input.push((opponent, outcome));
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
     }
 
-    parsed_input
-}
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
 
-pub fn get_answer(tokens: &Vec<(Pick, Outcome)>) -> i32 {
-    let mut sum = 0;
-    for line in tokens {
-        let (opponent, outcome) = line;
-        let you = Pick::calculate(opponent, outcome);
-        sum += outcome.value() + you.value();
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
-    sum
 }
 
-pub const CORRECT_ANSWER: i32 = 12725;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("A Y\nB W\n"),
+            Err(ParseError::new(2, 2, 3, "W", "X, Y or Z"))
+        );
+        assert_eq!(
+            parse_input("A Y\nB\n"),
+            Err(ParseError::new(2, 2, 2, "", "` `"))
+        );
+        assert_eq!(
+            parse_input(
+                "A Y
+D X
+"
+            ),
+            Err(ParseError::new(2, 2, 1, "D", "A, B or C"))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 15);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 12);
+    }
+
+    #[test]
+    fn test_interpretations() {
+        let tokens = parse_input(EXAMPLE).unwrap();
+        assert_eq!(
+            Interpretation::MOVES.round(Pick::ROCK, Column::Y),
+            (Pick::PAPER, Outcome::WIN)
+        );
+        assert_eq!(
+            Interpretation::OUTCOMES.round(Pick::ROCK, Column::Y),
+            (Pick::ROCK, Outcome::DRAW)
+        );
+
+        // X for scissors and Z for rock wins every round of the example
+        let swapped = Interpretation::Move([Pick::SCISSOR, Pick::PAPER, Pick::ROCK]);
+        assert_eq!(swapped.score(&tokens), (2 + 6) + (3 + 6) + (1 + 6));
+        let inverted = Interpretation::Outcome([Outcome::WIN, Outcome::DRAW, Outcome::LOSS]);
+        assert_eq!(inverted.score(&tokens), (1 + 3) + (3 + 6) + 2);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day2>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text: String = tokens
+                .iter()
+                .map(|(pick, column)| format!("{pick} {column}\n"))
+                .collect();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Scores with shape and outcome numbers 0 to 2, where the shape one
+    /// ahead of another beats it.
+    #[test]
+    fn test_reference() {
+        let shape = |pick: &Pick| *pick as i32;
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 50)).unwrap();
+            let (mut first, mut second) = (0, 0);
+            for (pick, column) in &tokens {
+                let (opponent, column) = (shape(pick), *column as i32);
+                first += column + 1 + 3 * (column - opponent + 1).rem_euclid(3);
+                second += (opponent + column - 1).rem_euclid(3) + 1 + 3 * column;
+            }
+            assert_eq!((part1(&tokens), part2(&tokens)), (first, second));
+        }
+    }
+}
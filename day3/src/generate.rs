@@ -0,0 +1,67 @@
+use aoc_common::Rng;
+
+/// Rucksacks in the puzzle input.
+pub const SIZE: usize = 300;
+
+const ITEMS: &[u8; 52] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// `size` rucksacks, rounded up to whole groups of three, each with exactly
+/// one item type in both compartments and each group sharing exactly one badge.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+
+    for _ in 0..size.div_ceil(3) {
+        let mut items = *ITEMS;
+        rng.shuffle(&mut items);
+        let (badge, rest) = items.split_first().unwrap();
+
+        // the three elves draw from disjoint items, so only the badge is shared
+        for pool in rest.chunks(17) {
+            let (shared, pool) = pool.split_first().unwrap();
+            let (left, right) = pool.split_at(8);
+            let length = rng.range(2..=16) as usize;
+
+            let mut first = vec![*shared, *badge];
+            first.extend((2..length).map(|_| *rng.pick(left)));
+            rng.shuffle(&mut first);
+            let mut second = vec![*shared];
+            second.extend((1..length).map(|_| *rng.pick(right)));
+            rng.shuffle(&mut second);
+
+            input.extend(first.into_iter().chain(second).map(char::from));
+            input.push('\n');
+        }
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_duplicate, get_shared, parse_input};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 30), generate(3, 30));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 30)).unwrap();
+            assert_eq!(tokens.len(), 30);
+            for group in tokens.chunks(3) {
+                let badge = get_duplicate(&group[0], &group[1], &group[2]).unwrap();
+                let mut common = group[0]
+                    .chars()
+                    .filter(|c| group[1].contains(*c) && group[2].contains(*c));
+                assert!(common.all(|c| c == badge));
+
+                for rucksack in group {
+                    let (first, second) = rucksack.split_at(rucksack.len() / 2);
+                    let shared = get_shared(first, second).unwrap();
+                    let mut both = first.chars().filter(|c| second.contains(*c));
+                    assert!(both.all(|c| c == shared));
+                }
+            }
+        }
+    }
+}
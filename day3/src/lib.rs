@@ -1,16 +1,52 @@
-use std::fs::File;
-use std::io::prelude::*;
+pub mod generate;
+
+use aoc_common::{Lines, ParseError, Solution};
 
 type Tokens = Vec<String>;
 
-pub fn parse_input(mut file: File) -> Tokens {
-    let mut contents = String::with_capacity(10000);
-    file.read_to_string(&mut contents).unwrap();
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let tokens: Tokens = Lines::new(3, input)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.all(|line| {
+                while !line.is_empty() {
+                    line.satisfy(|ch| ch.is_ascii_alphabetic(), "an item letter")?;
+                }
+                let rucksack = line.line();
+                if rucksack.len() % 2 != 0 {
+                    return Err(line.error_at(rucksack, "an even number of items"));
+                }
+                Ok(rucksack.to_string())
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    // part 2 needs every elf in a group of three
+    if !tokens.len().is_multiple_of(3) {
+        return Err(ParseError::eof(
+            3,
+            input,
+            "a rucksack to finish the group of three",
+        ));
+    }
+
+    Ok(tokens)
+}
 
-    contents.split_whitespace().map(|s| s.to_string()).collect()
+pub fn part1(tokens: &Tokens) -> i32 {
+    let mut sum = 0;
+
+    for rucksack in tokens {
+        let (first, second) = rucksack.split_at(rucksack.len() / 2);
+        if let Some(shared) = get_shared(first, second) {
+            sum += get_item_value(shared);
+        }
+    }
+
+    sum
 }
 
-pub fn get_answer(tokens: &Tokens) -> i32 {
+pub fn part2(tokens: &Tokens) -> i32 {
     let mut sum = 0;
 
     let i = tokens.len() / 3;
@@ -18,7 +54,7 @@ pub fn get_answer(tokens: &Tokens) -> i32 {
     for l in 0..i {
         let l = 3 * l;
         let (a, b, c) = (&tokens[l], &tokens[l + 1], &tokens[l + 2]);
-        if let Some(duplicate) = get_duplicate(&a, &b, &c) {
+        if let Some(duplicate) = get_duplicate(a, b, c) {
             sum += get_item_value(duplicate);
         }
     }
@@ -28,13 +64,17 @@ pub fn get_answer(tokens: &Tokens) -> i32 {
 
 fn get_item_value(c: char) -> i32 {
     let value = c as i32;
-    if value >= 64 && value <= 90 {
-        return value - 38;
+    if (64..=90).contains(&value) {
+        value - 38
     } else {
-        return value - 96;
+        value - 96
     }
 }
 
+fn get_shared(first: &str, second: &str) -> Option<char> {
+    first.chars().find(|c| second.contains(*c))
+}
+
 fn get_duplicate(first: &str, second: &str, third: &str) -> Option<char> {
     for c_first in first.chars() {
         for c_second in second.chars() {
@@ -49,6 +89,28 @@ fn get_duplicate(first: &str, second: &str, third: &str) -> Option<char> {
     None
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Tokens;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,11 +125,98 @@ mod tests {
         assert_eq!(duplicate, Some('d'));
     }
 
+    #[test]
+    fn test_shared() {
+        assert_eq!(get_shared("vJrwpWtwJgWr", "hcsFMMfFFhFp"), Some('p'));
+        assert_eq!(get_shared("abc", "def"), None);
+    }
+
     #[test]
     fn test_item_value() {
         assert_eq!(get_item_value('A'), 27);
         assert_eq!(get_item_value('a'), 1);
     }
-}
 
-pub const CORRECT_ANSWER: i32 = 8105;
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("abcD\nab1d\n"),
+            Err(ParseError::new(3, 2, 3, "1", "an item letter"))
+        );
+        assert_eq!(
+            parse_input("abc\n"),
+            Err(ParseError::new(3, 1, 1, "abc", "an even number of items"))
+        );
+        assert_eq!(
+            parse_input(
+                "abcD
+abcd
+abcD
+ab
+"
+            ),
+            Err(ParseError::new(
+                3,
+                5,
+                1,
+                "",
+                "a rucksack to finish the group of three"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 157);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 70);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day3>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 12)).unwrap();
+            assert_eq!(parse_input(&tokens.join("\n")).unwrap(), tokens);
+        }
+    }
+
+    #[test]
+    fn test_reference() {
+        use std::collections::HashSet;
+
+        let priority = |items: HashSet<char>| -> i32 {
+            let letters = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+            items
+                .iter()
+                .map(|item| letters.find(*item).unwrap() as i32 + 1)
+                .sum()
+        };
+        let set = |items: &str| items.chars().collect::<HashSet<char>>();
+
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 30)).unwrap();
+            let first = tokens
+                .iter()
+                .map(|rucksack| {
+                    let (left, right) = rucksack.split_at(rucksack.len() / 2);
+                    priority(&set(left) & &set(right))
+                })
+                .sum();
+            let second = tokens
+                .chunks(3)
+                .map(|group| priority(&(&set(&group[0]) & &set(&group[1])) & &set(&group[2])))
+                .sum();
+            assert_eq!((part1(&tokens), part2(&tokens)), (first, second));
+        }
+    }
+}
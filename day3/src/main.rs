@@ -1,11 +1,8 @@
-use std::fs::File;
+use std::process::ExitCode;
 
-use day3::*;
+use aoc_common::run_day;
+use day3::Day3;
 
-fn main() {
-    let parsed_input = parse_input(File::open("input/input.txt").unwrap());
-
-    let answer = get_answer(&parsed_input);
-
-    assert_eq!(answer, CORRECT_ANSWER);
+fn main() -> ExitCode {
+    run_day::<Day3>()
 }
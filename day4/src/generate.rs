@@ -0,0 +1,37 @@
+use aoc_common::Rng;
+
+/// Pairs in the puzzle input.
+pub const SIZE: usize = 1000;
+
+/// `size` pairs of section ranges between 1 and 99.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut range = || {
+        let start = rng.range(1..=99);
+        (start, rng.range(start..=99))
+    };
+
+    (0..size)
+        .map(|_| {
+            let (first, second) = (range(), range());
+            format!("{}-{},{}-{}\n", first.0, first.1, second.0, second.1)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 50), generate(3, 50));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 50)).unwrap();
+            assert_eq!(tokens.len(), 50);
+            // pairs that contain each other also overlap
+            assert!(part1(&tokens) <= part2(&tokens));
+        }
+    }
+}
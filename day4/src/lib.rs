@@ -1,30 +1,36 @@
-use std::fs::File;
-use std::io::prelude::*;
+pub mod generate;
+
+use aoc_common::{Line, Lines, ParseError, Solution};
 
 type Tokens = Vec<(CleaningRange, CleaningRange)>;
 
-pub fn parse_input(mut file: File) -> Tokens {
-    let mut contents = String::with_capacity(10000);
-    file.read_to_string(&mut contents).unwrap();
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    Lines::new(4, input).parse_each(|line| {
+        let first = parse_range(line)?;
+        line.literal(",")?;
+        Ok((first, parse_range(line)?))
+    })
+}
 
-    let mut tokens = Vec::<(CleaningRange, CleaningRange)>::new();
+fn parse_range(line: &mut Line) -> Result<CleaningRange, ParseError> {
+    let start = line.unsigned("a section number")?;
+    line.literal("-")?;
+    Ok((start, line.unsigned("a section number")?))
+}
 
-    for line in contents.lines() {
-        let parsed: Vec<i32> = line
-            .split(&[',', '-'])
-            .map(|c| c.parse().unwrap())
-            .collect();
+pub fn part1(tokens: &Tokens) -> i32 {
+    let mut sum = 0;
 
-        assert_eq!(parsed.len(), 4);
-        if let [first, second, third, firth] = parsed[..] {
-            tokens.push(((first, second), (third, firth)));
+    for token in tokens {
+        if fully_contains(&token.0, &token.1) || fully_contains(&token.1, &token.0) {
+            sum += 1;
         }
     }
 
-    tokens
+    sum
 }
 
-pub fn get_answer(tokens: &Tokens) -> i32 {
+pub fn part2(tokens: &Tokens) -> i32 {
     let mut sum = 0;
 
     for token in tokens {
@@ -38,6 +44,10 @@ pub fn get_answer(tokens: &Tokens) -> i32 {
 
 type CleaningRange = (i32, i32);
 
+fn fully_contains(outer: &CleaningRange, inner: &CleaningRange) -> bool {
+    outer.0 <= inner.0 && inner.1 <= outer.1
+}
+
 fn overlaps_at_all(first: &CleaningRange, second: &CleaningRange) -> bool {
     if first.1 - first.0 > second.1 - second.0 {
         if second.0 >= first.0 && second.0 <= first.1 || second.1 >= first.0 && second.1 <= first.1
@@ -54,6 +64,28 @@ fn overlaps_at_all(first: &CleaningRange, second: &CleaningRange) -> bool {
     false
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Tokens;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,8 +94,79 @@ mod tests {
     fn test_overlaps_at_all() {
         let first = (1, 3);
         let second = (3, 4);
-        assert_eq!(overlaps_at_all(&second, &first), true);
+        assert!(overlaps_at_all(&second, &first));
+    }
+
+    #[test]
+    fn test_fully_contains() {
+        assert!(fully_contains(&(2, 8), &(3, 7)));
+        assert!(fully_contains(&(4, 6), &(6, 6)));
+        assert!(!fully_contains(&(5, 7), &(7, 9)));
     }
-}
 
-pub const CORRECT_ANSWER: i32 = 839;
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("2-4,6-8\n2-3,4-x\n"),
+            Err(ParseError::new(4, 2, 7, "x", "a section number"))
+        );
+        assert_eq!(
+            parse_input("2-4,6\n"),
+            Err(ParseError::new(4, 1, 6, "", "`-`"))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 2);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 4);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day4>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text: String = tokens
+                .iter()
+                .map(|(first, second)| {
+                    format!("{}-{},{}-{}\n", first.0, first.1, second.0, second.1)
+                })
+                .collect();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Compares the sections of each range one by one.
+    #[test]
+    fn test_reference() {
+        use std::collections::HashSet;
+
+        let sections = |range: &CleaningRange| (range.0..=range.1).collect::<HashSet<i32>>();
+
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 100)).unwrap();
+            let (mut first, mut second) = (0, 0);
+            for (left, right) in &tokens {
+                let (left, right) = (sections(left), sections(right));
+                if left.is_subset(&right) || right.is_subset(&left) {
+                    first += 1;
+                }
+                if !left.is_disjoint(&right) {
+                    second += 1;
+                }
+            }
+            assert_eq!((part1(&tokens), part2(&tokens)), (first, second));
+        }
+    }
+}
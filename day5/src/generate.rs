@@ -0,0 +1,77 @@
+use aoc_common::Rng;
+
+/// Rearrangement steps in the puzzle input.
+pub const SIZE: usize = 500;
+
+/// Stacks in the puzzle input, also the most the drawing's one digit stack
+/// numbers allow.
+pub const STACKS: usize = 9;
+
+/// A drawing of [`STACKS`] stacks followed by `size` steps.
+pub fn generate(seed: u64, size: usize) -> String {
+    generate_stacks(seed, STACKS, size)
+}
+
+/// A drawing of `stacks` stacks, clamped to 1 to 9, of up to 8 crates each
+/// followed by `moves` steps that never take more crates than a stack holds.
+pub fn generate_stacks(seed: u64, stacks: usize, moves: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let stacks = stacks.clamp(1, STACKS);
+
+    let mut heights: Vec<usize> = (0..stacks).map(|_| rng.range(1..=8) as usize).collect();
+    let tallest = *heights.iter().max().unwrap();
+
+    let mut input = String::new();
+    for level in (0..tallest).rev() {
+        let row: Vec<String> = heights
+            .iter()
+            .map(|height| {
+                if level < *height {
+                    format!("[{}]", char::from(b'A' + rng.below(26) as u8))
+                } else {
+                    String::from("   ")
+                }
+            })
+            .collect();
+        input += &row.join(" ");
+        input.push('\n');
+    }
+    let numbers: Vec<String> = (1..=stacks).map(|stack| format!(" {stack} ")).collect();
+    input += &numbers.join(" ");
+    input += "\n\n";
+
+    for _ in 0..moves {
+        let filled: Vec<usize> = (0..stacks).filter(|stack| heights[*stack] > 0).collect();
+        let from = *rng.pick(&filled);
+        let to = match stacks {
+            1 => from,
+            _ => (from + 1 + rng.index(stacks - 1)) % stacks,
+        };
+        let count = rng.range(1..=heights[from] as i64) as usize;
+        heights[from] -= count;
+        heights[to] += count;
+        input += &format!("move {count} from {} to {}\n", from + 1, to + 1);
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 50), generate(3, 50));
+        for (seed, stacks) in (0..10).zip(1..) {
+            let (drawing, tokens) = parse_input(&generate_stacks(seed, stacks, 50)).unwrap();
+            assert_eq!(drawing.len(), stacks.min(STACKS));
+            assert_eq!(tokens.len(), 50);
+            assert_eq!(
+                part1(&drawing, &tokens).len(),
+                part2(&drawing, &tokens).len()
+            );
+        }
+    }
+}
@@ -1,61 +1,120 @@
-use std::fs::File;
-use std::io::prelude::*;
+pub mod generate;
+
+use aoc_common::{Line, Lines, ParseError, Solution};
 
 type Tokens = Vec<(i32, usize, usize)>;
 type Stack = Vec<u8>;
 
-pub fn parse_input(mut file: File) -> (Vec<Stack>, Tokens) {
-    let mut contents = String::with_capacity(10000);
-    file.read_to_string(&mut contents).unwrap();
+pub fn parse_input(input: &str) -> Result<(Vec<Stack>, Tokens), ParseError> {
+    let mut blocks = Lines::new(5, input).blocks();
+    let Some(drawing) = blocks.next() else {
+        return Err(ParseError::eof(5, input, "a drawing of the stacks"));
+    };
 
-    let mut tokens = Tokens::new();
-    let mut reading_stacks = true;
-    let mut stacks = [
-        Stack::new(),
-        Stack::new(),
-        Stack::new(),
-        Stack::new(),
-        Stack::new(),
-        Stack::new(),
-        Stack::new(),
-        Stack::new(),
-        Stack::new(),
-    ];
-    let mut raw_stacks = Vec::<String>::new();
-    for line in contents.lines() {
-        if reading_stacks && !line.contains("1") {
-            let line = line.to_string();
-            assert_eq!(line.len(), 36);
-            raw_stacks.push(line.clone());
-            continue;
+    let mut rows: Vec<Line> = drawing.collect();
+    let numbers = rows.pop().unwrap();
+    let mut stacks = vec![Stack::new(); numbers.all(parse_numbers)?];
+    for row in rows.into_iter().rev() {
+        let crates = row.all(parse_row)?;
+        if crates.len() > stacks.len() {
+            return Err(row.error_at(row.line(), "no crates beside the stacks"));
         }
-        if line.contains("1") && reading_stacks {
-            reading_stacks = false;
-            raw_stacks.reverse();
-            for line in &raw_stacks {
-                for i in 0..9 {
-                    let tmp = line[i * 4..i * 4 + 4].to_string();
-                    if tmp.contains('[') {
-                        stacks[i].push(tmp.as_bytes()[1]);
-                    }
-                }
+        for (stack, label) in stacks.iter_mut().zip(crates) {
+            stack.extend(label);
+        }
+    }
+
+    // steps may not take more crates than the stack holds by then
+    let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+    let mut tokens = Tokens::new();
+    for block in blocks {
+        tokens.extend(block.parse_each(|line| {
+            line.literal("move ")?;
+            let at = *line;
+            let count = line.unsigned::<i32>("a number of crates")?;
+            line.literal(" from ")?;
+            let from = parse_stack(line, stacks.len())?;
+            line.literal(" to ")?;
+            let to = parse_stack(line, stacks.len())?;
+
+            if count as usize > heights[from] {
+                let expected = format!("at most {} crates on stack {}", heights[from], from + 1);
+                return Err(at.error(&expected));
             }
-            continue;
+            heights[from] -= count as usize;
+            heights[to] += count as usize;
+            Ok((count, from, to))
+        })?);
+    }
+
+    Ok((stacks, tokens))
+}
+
+/// Parses the ` 1   2   3 ` line under the drawing, returning the number of
+/// stacks.
+fn parse_numbers(line: &mut Line) -> Result<usize, ParseError> {
+    let mut count = 0;
+    line.take_while(|ch| ch == ' ');
+    while !line.is_empty() {
+        let before = *line;
+        if line.unsigned::<usize>("a stack number")? != count + 1 {
+            return Err(before.error(&format!("stack number {}", count + 1)));
         }
-        if line.is_empty() {
-            continue;
+        count += 1;
+        line.take_while(|ch| ch == ' ');
+    }
+
+    Ok(count)
+}
+
+/// Parses a row of crates separated by single spaces, rows may be padded
+/// with one more space.
+fn parse_row(line: &mut Line) -> Result<Vec<Option<u8>>, ParseError> {
+    let mut crates = Vec::new();
+    while !line.is_empty() {
+        crates.push(parse_crate(line)?);
+        if !line.eat(" ") {
+            break;
         }
-        let split: Vec<&str> = line.split(' ').collect();
-        let stack = split[1].parse().unwrap();
-        let from: i32 = split[3].parse::<i32>().unwrap() - 1;
-        let to: i32 = split[5].parse::<i32>().unwrap() - 1;
-        tokens.push((stack, from as usize, to as usize));
     }
 
-    (stacks.to_vec(), tokens)
+    Ok(crates)
 }
 
-pub fn get_answer(stacks: &mut Vec<Stack>, tokens: &Tokens) -> String {
+/// Parses `[X]`, or three spaces where a stack has no crate at this height.
+fn parse_crate(line: &mut Line) -> Result<Option<u8>, ParseError> {
+    if line.eat("   ") {
+        return Ok(None);
+    }
+    line.literal("[")?;
+    let label = line.satisfy(|ch| ch.is_ascii_uppercase(), "a crate letter")?;
+    line.literal("]")?;
+    Ok(Some(label as u8))
+}
+
+/// Parses a 1-based stack number into an index into `count` stacks.
+fn parse_stack(line: &mut Line, count: usize) -> Result<usize, ParseError> {
+    let before = *line;
+    match line.unsigned::<usize>("an existing stack number")? {
+        stack if (1..=count).contains(&stack) => Ok(stack - 1),
+        _ => Err(before.error("an existing stack number")),
+    }
+}
+
+pub fn part1(stacks: &[Stack], tokens: &Tokens) -> String {
+    let mut stacks = stacks.to_vec();
+    for (n, from, to) in tokens {
+        for _ in 0..*n {
+            let p = stacks[*from].pop().unwrap();
+            stacks[*to].push(p);
+        }
+    }
+
+    top_crates(&stacks)
+}
+
+pub fn part2(stacks: &[Stack], tokens: &Tokens) -> String {
+    let mut stacks = stacks.to_vec();
     for (n, from, to) in tokens {
         let mut tmp = Vec::<u8>::new();
         for _ in 0..*n {
@@ -68,13 +127,164 @@ pub fn get_answer(stacks: &mut Vec<Stack>, tokens: &Tokens) -> String {
         }
     }
 
+    top_crates(&stacks)
+}
+
+fn top_crates(stacks: &[Stack]) -> String {
     let mut result = String::new();
     for stack in stacks {
-        let a = char::from_u32(*stack.last().unwrap() as u32).unwrap();
-        result.push(a);
+        if let Some(top) = stack.last() {
+            result.push(char::from(*top));
+        }
     }
 
     result
 }
 
-pub const CORRECT_ANSWER: &str = "RNRGDNFQG";
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = (Vec<Stack>, Tokens);
+    type Output1 = String;
+    type Output2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(&input.0, &input.1)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(&input.0, &input.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_stacks() {
+        let (stacks, tokens) = parse_input(EXAMPLE).unwrap();
+        assert_eq!(
+            stacks,
+            Vec::from([b"ZN".to_vec(), b"MCD".to_vec(), b"P".to_vec()])
+        );
+        assert_eq!(tokens.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = EXAMPLE.replace("from 1 to 3", "from 1 to 4");
+        assert_eq!(
+            parse_input(&input),
+            Err(ParseError::new(5, 7, 18, "4", "an existing stack number"))
+        );
+        let input = EXAMPLE.replace("move 2", "mvoe 2");
+        assert_eq!(
+            parse_input(&input),
+            Err(ParseError::new(5, 8, 1, "mvoe", "`move `"))
+        );
+        assert_eq!(
+            parse_input("[A]\n 1 \n\nmove 2 from 1 to 1\n"),
+            Err(ParseError::new(5, 4, 6, "2", "at most 1 crates on stack 1"))
+        );
+        let input = EXAMPLE.replace("move 1 from 1 to 2", "move 4 from 1 to 2");
+        assert_eq!(
+            parse_input(&input),
+            Err(ParseError::new(5, 9, 6, "4", "at most 2 crates on stack 1"))
+        );
+        assert_eq!(
+            parse_input("[A]\n"),
+            Err(ParseError::new(5, 1, 1, "[", "a stack number"))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        let (stacks, tokens) = parse_input(EXAMPLE).unwrap();
+        assert_eq!(part1(&stacks, &tokens), "CMZ");
+    }
+
+    #[test]
+    fn test_part2() {
+        let (stacks, tokens) = parse_input(EXAMPLE).unwrap();
+        assert_eq!(part2(&stacks, &tokens), "MCD");
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day5>(300, |seed| generate::generate(seed, 5));
+    }
+
+    /// Draws the stacks and lists the steps like the puzzle input.
+    fn format_input(stacks: &[Stack], tokens: &Tokens) -> String {
+        let tallest = stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let mut text = String::new();
+        for level in (0..tallest).rev() {
+            let row: Vec<String> = stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(label) => format!("[{}]", char::from(*label)),
+                    None => String::from("   "),
+                })
+                .collect();
+            text += &format!("{}\n", row.join(" "));
+        }
+        let numbers: Vec<String> = (1..=stacks.len()).map(|n| format!(" {n} ")).collect();
+        text += &format!("{}\n\n", numbers.join(" "));
+        for (count, from, to) in tokens {
+            text += &format!("move {count} from {} to {}\n", from + 1, to + 1);
+        }
+        text
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let (stacks, tokens) = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text = format_input(&stacks, &tokens);
+            assert_eq!(parse_input(&text).unwrap(), (stacks, tokens));
+        }
+    }
+
+    /// Moves the crates as slices of text, reversed when they are moved one at
+    /// a time.
+    #[test]
+    fn test_reference() {
+        for seed in 0..20 {
+            let (stacks, tokens) = parse_input(&generate::generate(seed, 50)).unwrap();
+            let mut expected: Vec<String> = Vec::new();
+            for one_at_a_time in [true, false] {
+                let mut stacks: Vec<String> = stacks
+                    .iter()
+                    .map(|stack| String::from_utf8(stack.clone()).unwrap())
+                    .collect();
+                for (count, from, to) in &tokens {
+                    let at = stacks[*from].len() - *count as usize;
+                    let mut moved = stacks[*from].split_off(at);
+                    if one_at_a_time {
+                        moved = moved.chars().rev().collect();
+                    }
+                    stacks[*to] += &moved;
+                }
+                expected.push(
+                    stacks
+                        .iter()
+                        .filter_map(|stack| stack.chars().last())
+                        .collect(),
+                );
+            }
+            assert_eq!(
+                vec![part1(&stacks, &tokens), part2(&stacks, &tokens)],
+                expected
+            );
+        }
+    }
+}
@@ -1,11 +1,8 @@
-use std::fs::File;
+use std::process::ExitCode;
 
-use day5::*;
+use aoc_common::run_day;
+use day5::Day5;
 
-fn main() {
-    let (mut stacks, tokens) = parse_input(File::open("input/input.txt").unwrap());
-
-    let answer = get_answer(&mut stacks, &tokens);
-
-    assert_eq!(answer, CORRECT_ANSWER);
+fn main() -> ExitCode {
+    run_day::<Day5>()
 }
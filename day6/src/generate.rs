@@ -0,0 +1,44 @@
+use aoc_common::Rng;
+
+/// Characters in the puzzle input.
+pub const SIZE: usize = 4096;
+
+const LETTERS: &[u8; 26] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// A datastream buffer of `size` letters, at least 14, drawn from a handful
+/// of letters so markers are rare, with one run of 14 distinct letters.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let size = size.max(14);
+
+    let mut letters = *LETTERS;
+    rng.shuffle(&mut letters);
+    // too few letters for a start-of-message marker anywhere else
+    let alphabet = &letters[..rng.range(4..=13) as usize];
+    let mut buffer: Vec<u8> = (0..size).map(|_| *rng.pick(alphabet)).collect();
+
+    let mut message = *LETTERS;
+    rng.shuffle(&mut message);
+    let start = rng.index(size - 13);
+    buffer[start..start + 14].copy_from_slice(&message[..14]);
+
+    let mut input = String::from_utf8(buffer).unwrap();
+    input.push('\n');
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 100), generate(3, 100));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 100)).unwrap();
+            assert_eq!(tokens.len(), 100);
+            assert!(part1(&tokens) <= part2(&tokens));
+        }
+    }
+}
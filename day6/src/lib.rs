@@ -1,28 +1,58 @@
-use std::fs::File;
-use std::io::prelude::*;
+pub mod generate;
+
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use aoc_common::{Error, Lines, ParseError, Solution, Streaming};
 
 type Tokens = Vec<u8>;
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+/// Sizes of the start-of-packet and start-of-message markers.
+const MARKERS: [(usize, &str); 2] = [
+    (4, "a start-of-packet marker"),
+    (14, "a start-of-message marker"),
+];
 
-    let mut contents = String::with_capacity(10000);
+/// Error for a buffer of `length` letters without the marker `expected`.
+fn missing(length: usize, expected: &str) -> ParseError {
+    ParseError::new(6, 1, length + 1, "", expected)
+}
+
+/// The buffer must hold both markers.
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let mut lines = Lines::new(6, input);
+    let buffer = lines.next_line("a datastream buffer")?.all(|line| {
+        while !line.is_empty() {
+            line.satisfy(|ch| ch.is_ascii_lowercase(), "a lowercase letter")?;
+        }
+        Ok(line.line().as_bytes().to_vec())
+    })?;
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
+    if let Some(extra) = lines.find(|line| !line.is_empty()) {
+        return Err(extra.error("a single datastream buffer"));
     }
+    for (size, expected) in MARKERS {
+        if find_marker(&buffer, size).is_none() {
+            return Err(missing(buffer.len(), expected));
+        }
+    }
+
+    Ok(buffer)
+}
+
+pub fn part1(tokens: &Tokens) -> usize {
+    find_marker(tokens, 4).expect("parse_input checks there is a marker")
 }
 
-pub fn parse_input(input: &String) -> Tokens {
-    input.as_bytes().to_vec()
+pub fn part2(tokens: &Tokens) -> usize {
+    find_marker(tokens, 14).expect("parse_input checks there is a marker")
 }
 
-pub fn get_answer(tokens: &Tokens) -> i32 {
+/// Letters read up to the end of the first `size` distinct ones.
+fn find_marker(tokens: &[u8], size: usize) -> Option<usize> {
     let mut num = 0;
 
-    'outer: for window in tokens.windows(14) {
+    'outer: for window in tokens.windows(size) {
         let mut window = window.to_vec();
         window.sort();
         for subwindow in window.windows(2) {
@@ -32,22 +62,224 @@ pub fn get_answer(tokens: &Tokens) -> i32 {
             }
         }
 
-        return num + 14;
+        return Some(num + size);
+    }
+
+    None
+}
+
+/// The first `size` distinct letters in a stream of lowercase letters.
+struct Marker {
+    size: usize,
+    recent: VecDeque<u8>,
+    end: Option<usize>,
+}
+
+impl Marker {
+    fn new(size: usize) -> Self {
+        Self {
+            size,
+            recent: VecDeque::with_capacity(size + 1),
+            end: None,
+        }
+    }
+
+    /// Adds the letter at `position`, counted from 1.
+    fn push(&mut self, position: usize, letter: u8) {
+        if self.end.is_some() {
+            return;
+        }
+
+        self.recent.push_back(letter);
+        if self.recent.len() > self.size {
+            self.recent.pop_front();
+        }
+        let seen = self
+            .recent
+            .iter()
+            .fold(0u32, |seen, letter| seen | 1 << (letter - b'a'));
+        if seen.count_ones() as usize == self.size {
+            self.end = Some(position);
+        }
+    }
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Tokens;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
     }
 
-    unreachable!("There must be answer before end of tokens!")
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+impl Streaming for Day6 {
+    /// Reads the buffer a byte at a time, keeping only the last 14 letters.
+    fn stream<R: BufRead>(reader: R) -> aoc_common::Result<(usize, usize)> {
+        let [mut packet, mut message] = MARKERS.map(|(size, _)| Marker::new(size));
+        let mut line = 1;
+        let mut column = 0;
+
+        for byte in reader.bytes() {
+            let byte = byte.map_err(|error| Error::Read(String::from("<input>"), error))?;
+            match byte {
+                b'\n' => line += 1,
+                b'\r' => {}
+                b'a'..=b'z' if line == 1 => {
+                    column += 1;
+                    packet.push(column, byte);
+                    message.push(column, byte);
+                }
+                _ if line == 1 => {
+                    let text = (byte as char).to_string();
+                    return Err(
+                        ParseError::new(6, 1, column + 1, &text, "a lowercase letter").into(),
+                    );
+                }
+                _ => {
+                    let text = (byte as char).to_string();
+                    return Err(
+                        ParseError::new(6, line, 1, &text, "a single datastream buffer").into(),
+                    );
+                }
+            }
+        }
+
+        match (packet.end, message.end) {
+            (Some(packet), Some(message)) => Ok((packet, message)),
+            (None, _) => Err(missing(column, MARKERS[0].1).into()),
+            (_, None) => Err(missing(column, MARKERS[1].1).into()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // one datastream buffer per line
+    const EXAMPLES: &str = include_str!("../input/example.txt");
+
     #[test]
     fn test_get_answer() {
         let tokens = Vec::<u8>::from([3, 5, 1, 7, 8, 1, 9, 7, 2]);
-        let answer = get_answer(&tokens);
+        let answer = part1(&tokens);
         assert_eq!(answer, 4);
     }
-}
 
-pub const CORRECT_ANSWER: i32 = 3298;
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("abc\nabd\n"),
+            Err(ParseError::new(
+                6,
+                2,
+                1,
+                "abd",
+                "a single datastream buffer"
+            ))
+        );
+        assert_eq!(
+            parse_input("ab-c\n"),
+            Err(ParseError::new(6, 1, 3, "-", "a lowercase letter"))
+        );
+    }
+
+    #[test]
+    fn test_no_marker() {
+        for (input, expected) in [
+            ("aaaa\n", "a start-of-packet marker"),
+            ("abcd\r\n", "a start-of-message marker"),
+            ("", "a datastream buffer"),
+        ] {
+            let parsed = parse_input(input).unwrap_err();
+            assert_eq!(parsed.expected, expected);
+            if !input.is_empty() {
+                let Err(Error::Parse(streamed)) = Day6::stream(input.as_bytes()) else {
+                    panic!("{input:?} has no marker");
+                };
+                assert_eq!(streamed, parsed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream() {
+        for (buffer, expected) in
+            EXAMPLES
+                .lines()
+                .zip([(7, 19), (5, 23), (6, 23), (10, 29), (11, 26)])
+        {
+            assert_eq!(Day6::stream(buffer.as_bytes()).unwrap(), expected);
+        }
+        assert!(matches!(
+            Day6::stream("abcd\r\n".as_bytes()),
+            Err(Error::Parse(error)) if error.expected == "a start-of-message marker"
+        ));
+        assert!(matches!(
+            Day6::stream("ab-c\n".as_bytes()),
+            Err(Error::Parse(error)) if error == ParseError::new(6, 1, 3, "-", "a lowercase letter")
+        ));
+    }
+
+    #[test]
+    fn test_part1() {
+        for (buffer, expected) in EXAMPLES.lines().zip([7, 5, 6, 10, 11]) {
+            assert_eq!(part1(&parse_input(buffer).unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn test_part2() {
+        for (buffer, expected) in EXAMPLES.lines().zip([19, 23, 23, 29, 26]) {
+            assert_eq!(part2(&parse_input(buffer).unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day6>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 50)).unwrap();
+            let text = String::from_utf8(tokens.clone()).unwrap();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    #[test]
+    fn test_reference() {
+        use std::collections::HashSet;
+
+        let marker = |tokens: &[u8], size| {
+            let start = tokens
+                .windows(size)
+                .position(|window| window.iter().collect::<HashSet<_>>().len() == size);
+            start.unwrap() + size
+        };
+
+        for seed in 0..50 {
+            let input = generate::generate(seed, 100);
+            let tokens = parse_input(&input).unwrap();
+            let expected = (marker(&tokens, 4), marker(&tokens, 14));
+            assert_eq!((part1(&tokens), part2(&tokens)), expected);
+            assert_eq!(Day6::stream(input.as_bytes()).unwrap(), expected);
+        }
+    }
+}
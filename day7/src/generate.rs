@@ -0,0 +1,81 @@
+use aoc_common::Rng;
+
+/// Directories in the puzzle input.
+pub const SIZE: usize = 200;
+
+/// A transcript browsing a tree of `size` directories besides `/`, each
+/// listed once and holding up to five files of up to 300000 bytes.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    // every directory after the root picks an earlier one as its parent
+    let mut children = vec![Vec::new(); size + 1];
+    for dir in 1..=size {
+        children[rng.index(dir)].push(dir);
+    }
+
+    let mut input = String::from("$ cd /\n");
+    browse(&mut rng, &children, 0, &mut input);
+    input
+}
+
+fn browse(rng: &mut Rng, children: &[Vec<usize>], dir: usize, input: &mut String) {
+    input.push_str("$ ls\n");
+    let names: Vec<String> = children[dir].iter().map(|child| name(*child)).collect();
+    let mut entries: Vec<String> = names.iter().map(|name| format!("dir {name}")).collect();
+    // files always have an extension so they never clash with a directory
+    for file in 0..rng.range(0..=5) {
+        let size = rng.range(1..=300000);
+        let extension = *rng.pick(&[".txt", ".dat", ".log", ".bin"]);
+        entries.push(format!("{size} {}{extension}", name(file as usize)));
+    }
+    rng.shuffle(&mut entries);
+    for entry in entries {
+        input.push_str(&entry);
+        input.push('\n');
+    }
+
+    for (child, name) in children[dir].iter().zip(names) {
+        input.push_str(&format!("$ cd {name}\n"));
+        browse(rng, children, *child, input);
+        input.push_str("$ cd ..\n");
+    }
+}
+
+/// Distinct lowercase names for distinct numbers.
+fn name(mut number: usize) -> String {
+    let mut name = String::new();
+    loop {
+        name.push(char::from(b'a' + (number % 26) as u8));
+        number /= 26;
+        if number == 0 {
+            return name;
+        }
+        number -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dir_sizes, parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 30), generate(3, 30));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 30)).unwrap();
+            assert_eq!(dir_sizes(&tokens).len(), 31);
+            part1(&tokens);
+            part2(&tokens);
+        }
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(
+            [name(0), name(25), name(26), name(27)],
+            ["a", "z", "aa", "ba"]
+        );
+    }
+}
@@ -1,8 +1,11 @@
+pub mod generate;
+
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::prelude::*;
+use std::fmt;
+
+use aoc_common::{Lines, ParseError, Solution};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Command {
     CD(String),
     LS(Vec<Entry>),
@@ -10,73 +13,99 @@ pub enum Command {
 
 type Tokens = Vec<Command>;
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+/// The transcript must start in the root with `$ cd /`, so that every
+/// listing has a directory to go to.
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let lines = Lines::new(7, input);
+    let mut tokens = Tokens::new();
+    let mut root = false;
+
+    let mut ls = Vec::new();
+    for mut line in lines.clone() {
+        if !root && !line.is_empty() && !line.rest().starts_with("$ cd ") {
+            return Err(line.error("`$ cd /`"));
+        }
+        if line.eat("dir ") {
+            ls.push(Entry::DIR);
+            continue;
+        }
+        if line.rest().starts_with(|ch: char| ch.is_ascii_digit()) {
+            let size = line.unsigned("a file size")?;
+            line.literal(" ")?;
+            ls.push(Entry::FILE(size));
+            continue;
+        }
 
-    let mut contents = String::with_capacity(10000);
+        if !ls.is_empty() {
+            tokens.push(Command::LS(ls.clone()));
+            ls.clear();
+        }
+        if line.eat("$ cd ") {
+            let name = line.take_rest();
+            if !root && name != "/" {
+                return Err(line.error_at(name, "`/`"));
+            }
+            root = true;
+            tokens.push(Command::CD(name.to_string()));
+        } else if line.eat("$ ls") {
+            line.end()?;
+        } else if !line.is_empty() {
+            return Err(line.error("a command or a listing"));
+        }
+    }
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
+    if !ls.is_empty() {
+        tokens.push(Command::LS(ls));
     }
+    if !root {
+        return Err(lines.eof("`$ cd /`"));
+    }
+
+    Ok(tokens)
 }
 
-pub fn parse_input(input: &String) -> Tokens {
-    let mut tokens = Tokens::new();
+pub fn part1(tokens: &Tokens) -> i32 {
+    dir_sizes(tokens)
+        .into_values()
+        .filter(|size| *size <= 100000)
+        .sum()
+}
 
-    let mut ls = Vec::new();
-    let mut lines = input.lines();
-    loop {
-        match lines.next() {
-            Some(line) => {
-                if line[0..4] == *"$ cd" {
-                    if !ls.is_empty() {
-                        tokens.push(Command::LS(ls.clone()));
-                        ls.clear();
-                    }
-                    let s = String::from(line);
-                    let cd = Command::CD(s[5..].to_string());
-                    tokens.push(cd);
-                    continue;
-                } else if line == "$ ls" || line == "" {
-                    if !ls.is_empty() {
-                        tokens.push(Command::LS(ls.clone()));
-                        ls.clear();
-                    }
-                    continue;
-                } else {
-                    if line[0..3] == *"dir" {
-                        ls.push(Entry::DIR);
-                        continue;
-                    } else {
-                        let mut split = line.split_whitespace();
-                        let size = split.next().unwrap().parse::<i32>().unwrap();
-                        ls.push(Entry::FILE(size));
-                        continue;
-                    }
-                }
-            }
-            None => {
-                assert_eq!(ls.is_empty(), false);
-                tokens.push(Command::LS(ls.clone()));
-                break;
-            }
+pub fn part2(tokens: &Tokens) -> i32 {
+    let dirs_recursive_size = dir_sizes(tokens);
+
+    let root_size = dirs_recursive_size[ROOT];
+    let mut appropriate_dirs = Vec::new();
+
+    for (_, size) in dirs_recursive_size {
+        if size >= root_size - 40000000 {
+            appropriate_dirs.push(size);
         }
     }
 
-    tokens
+    appropriate_dirs.sort();
+    appropriate_dirs[0]
 }
 
-pub fn get_answer(tokens: &Tokens) -> i32 {
-    let mut dirs = HashMap::new();
-    let mut last_dir = String::new();
+/// Key of the root directory in [`dir_sizes`].
+const ROOT: &str = "//";
+
+/// Total size of every directory including its subdirectories, keyed by path.
+fn dir_sizes(tokens: &Tokens) -> HashMap<String, i32> {
+    let mut dirs = HashMap::from([(ROOT.to_string(), 0)]);
+    let mut last_dir = String::from(ROOT);
 
     for token in tokens {
         match token {
             Command::CD(name) => {
-                if name == ".." {
-                    assert_eq!(last_dir.remove(last_dir.len() - 1), '/');
+                if name == "/" {
+                    last_dir = String::from(ROOT);
+                } else if name == ".." {
+                    // there is nothing above the root
+                    if last_dir == ROOT {
+                        continue;
+                    }
+                    last_dir.pop();
                     loop {
                         match last_dir.pop() {
                             Some('/') => {
@@ -89,7 +118,7 @@ pub fn get_answer(tokens: &Tokens) -> i32 {
                     }
                 } else {
                     last_dir += (name.to_owned() + "/").to_string().as_str();
-                    assert_eq!(dirs.insert(last_dir.clone(), 0), None);
+                    dirs.entry(last_dir.clone()).or_insert(0);
                 }
             }
             Command::LS(entries) => {
@@ -97,8 +126,7 @@ pub fn get_answer(tokens: &Tokens) -> i32 {
                     match entry {
                         Entry::DIR => continue,
                         Entry::FILE(file_size) => {
-                            let Some(size) = dirs.get_mut(&last_dir) else {unreachable!("No directory entry!")};
-                            *size += *file_size;
+                            *dirs.entry(last_dir.clone()).or_insert(0) += *file_size;
                         }
                     }
                 }
@@ -115,32 +143,173 @@ pub fn get_answer(tokens: &Tokens) -> i32 {
             .filter(|k| k.starts_with(dir) && k != dir)
             .collect();
 
-        let Some(v) = dirs_recursive_size.get_mut(dir) else { unreachable!() };
+        let Some(v) = dirs_recursive_size.get_mut(dir) else {
+            unreachable!()
+        };
         for subdir in subdirs {
-            match dirs.get(&subdir) {
-                Some(a) => *v += a,
-                None => {}
+            if let Some(a) = dirs.get(&subdir) {
+                *v += a
             }
         }
     }
 
-    let root_size = dirs_recursive_size.get("//").unwrap().clone();
-    let mut appropriate_dirs = Vec::new();
-
-    for (_, size) in dirs_recursive_size {
-        if size >= root_size - 40000000 {
-            appropriate_dirs.push(size);
-        }
-    }
-
-    appropriate_dirs.sort();
-    appropriate_dirs[0]
+    dirs_recursive_size
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Entry {
     FILE(i32),
     DIR,
 }
 
-pub const CORRECT_ANSWER: i32 = 4443914;
+/// The command and, for `ls`, its listing. Entry names are not kept, so they
+/// are listed as `d0`, `f1` and so on.
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CD(name) => writeln!(f, "$ cd {name}"),
+            Self::LS(entries) => {
+                writeln!(f, "$ ls")?;
+                for (i, entry) in entries.iter().enumerate() {
+                    match entry {
+                        Entry::DIR => writeln!(f, "dir d{i}")?,
+                        Entry::FILE(size) => writeln!(f, "{size} f{i}")?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Tokens;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("$ cd /\n$ ls\n$\n"),
+            Err(ParseError::new(7, 3, 1, "$", "a command or a listing"))
+        );
+        assert_eq!(
+            parse_input("$ cd /\n$ ls\n12k a.txt\n"),
+            Err(ParseError::new(7, 3, 1, "12k", "a file size"))
+        );
+    }
+
+    #[test]
+    fn test_transcripts() {
+        assert_eq!(
+            parse_input("$ ls\n10 a\n"),
+            Err(ParseError::new(7, 1, 1, "$", "`$ cd /`"))
+        );
+        assert_eq!(
+            parse_input("10 a\n"),
+            Err(ParseError::new(7, 1, 1, "10", "`$ cd /`"))
+        );
+        assert_eq!(
+            parse_input("$ cd a\n$ ls\n10 b\n"),
+            Err(ParseError::new(7, 1, 6, "a", "`/`"))
+        );
+        assert_eq!(
+            parse_input(""),
+            Err(ParseError::new(7, 1, 1, "", "`$ cd /`"))
+        );
+
+        // revisiting a directory and going up from the root are fine
+        let tokens =
+            parse_input("$ cd /\n$ cd ..\n$ cd a\n$ ls\n10 b\n$ cd ..\n$ cd a\n$ ls\n5 c\n")
+                .unwrap();
+        assert_eq!((part1(&tokens), part2(&tokens)), (30, 15));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 95437);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 24933642);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day7>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text: String = tokens.iter().map(Command::to_string).collect();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Adds every file to each directory on its path.
+    #[test]
+    fn test_reference() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 30)).unwrap();
+            let mut path: Vec<&str> = Vec::new();
+            let mut dirs = Vec::new();
+            let mut files = Vec::new();
+            for token in &tokens {
+                match token {
+                    Command::CD(name) if name == ".." => {
+                        path.pop();
+                    }
+                    Command::CD(name) => {
+                        path.push(name);
+                        dirs.push(path.clone());
+                    }
+                    Command::LS(entries) => {
+                        for entry in entries {
+                            if let Entry::FILE(size) = entry {
+                                files.push((path.clone(), *size));
+                            }
+                        }
+                    }
+                }
+            }
+
+            let sizes: Vec<i32> = dirs
+                .iter()
+                .map(|dir| {
+                    let inside = files.iter().filter(|(path, _)| path.starts_with(dir));
+                    inside.map(|(_, size)| size).sum()
+                })
+                .collect();
+            let needed = sizes[0] - 40000000;
+            let first = sizes.iter().filter(|size| **size <= 100000).sum();
+            let second = *sizes.iter().filter(|size| **size >= needed).min().unwrap();
+            assert_eq!((part1(&tokens), part2(&tokens)), (first, second));
+        }
+    }
+}
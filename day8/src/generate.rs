@@ -0,0 +1,42 @@
+use aoc_common::Rng;
+
+/// Width and height of the puzzle input's grid.
+pub const SIZE: usize = 99;
+
+/// A `size` by `size` grid of tree heights, taller towards the middle like
+/// the puzzle's forest.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let size = size.max(1);
+    let middle = size as i64 / 2;
+
+    let mut input = String::new();
+    for y in 0..size as i64 {
+        for x in 0..size as i64 {
+            let edge = middle - (x - middle).abs().max((y - middle).abs());
+            let height = (edge * 10 / (middle + 1) + rng.range(-3..=3)).clamp(0, 9);
+            input.push(char::from(b'0' + height as u8));
+        }
+        input.push('\n');
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 20), generate(3, 20));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 20)).unwrap();
+            assert_eq!(tokens.width(), 20);
+            // at least the edge is visible, the edge trees see nothing
+            assert!(part1(&tokens) >= 4 * 19);
+            part2(&tokens);
+        }
+    }
+}
@@ -1,94 +1,74 @@
-use std::fs::File;
-use std::io::prelude::*;
+pub mod generate;
 
-type Tokens = Vec<Vec<u32>>;
+use aoc_common::{Grid, Image, ParseError, Point, Solution};
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+type Tokens = Grid<u32>;
 
-    let mut contents = String::with_capacity(10000);
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    let tokens = Grid::parse(8, input, "a tree height", |ch| ch.to_digit(10))?;
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
+    if tokens.width() != tokens.height() {
+        let line = input.lines().next().unwrap();
+        return Err(ParseError::at(8, 0, line, line, "a square grid of trees"));
     }
-}
 
-pub fn parse_input(input: &String) -> Tokens {
-    let mut tokens = Tokens::new();
+    Ok(tokens)
+}
 
-    for line in input.lines() {
-        let mut vec = Vec::new();
-        for char in line.chars() {
-            vec.push(char.to_digit(10).unwrap());
+pub fn part1(tokens: &Tokens) -> i32 {
+    let mut visible = 0;
+
+    for (point, height) in tokens.iter() {
+        let is_visible = [Side::LEFT, Side::RIGHT, Side::TOP, Side::BOTTOM]
+            .into_iter()
+            .any(|side| {
+                extract_side(tokens, point, side)
+                    .iter()
+                    .all(|tree| tree < height)
+            });
+        if is_visible {
+            visible += 1;
         }
-        tokens.push(vec);
     }
 
-    tokens
+    visible
 }
 
-pub fn get_answer(tokens: &Tokens) -> i32 {
-    assert_eq!(tokens.len(), tokens[0].len());
-    let size = tokens.len();
-
+pub fn part2(tokens: &Tokens) -> i32 {
     let mut highest_scenic_score = 0;
 
-    for i in 1..size - 1 {
-        for j in 1..size - 1 {
-            let height = tokens[i][j];
-            let (l, r, t, b) = (
-                scenic_score(height, &extract_side(&tokens, i, j, Side::LEFT), Side::LEFT),
-                scenic_score(
-                    height,
-                    &extract_side(&tokens, i, j, Side::RIGHT),
-                    Side::RIGHT,
-                ),
-                scenic_score(height, &extract_side(&tokens, i, j, Side::TOP), Side::TOP),
-                scenic_score(
-                    height,
-                    &extract_side(&tokens, i, j, Side::BOTTOM),
-                    Side::BOTTOM,
-                ),
-            );
-            let candidate = l * r * t * b;
-            if candidate > highest_scenic_score {
-                highest_scenic_score = candidate;
-            }
+    for (point, height) in tokens.iter() {
+        let candidate: i32 = [Side::LEFT, Side::RIGHT, Side::TOP, Side::BOTTOM]
+            .into_iter()
+            .map(|side| {
+                let trees = extract_side(tokens, point, side.clone());
+                scenic_score(*height, &trees, side)
+            })
+            .product();
+        if candidate > highest_scenic_score {
+            highest_scenic_score = candidate;
         }
     }
 
     highest_scenic_score
 }
 
-fn extract_side(tokens: &Vec<Vec<u32>>, i: usize, j: usize, side: Side) -> Vec<u32> {
-    let row = {
-        if side == Side::LEFT || side == Side::RIGHT {
-            tokens[i].clone()
-        } else {
-            let mut row = Vec::new();
-            for r in tokens {
-                row.push(r[j]);
-            }
-            row
-        }
-    };
+/// Tree heights from black for 0 to white for 9, the same for both parts.
+pub fn image(tokens: &Tokens, _part: u8) -> Image {
+    Image::Grey(tokens.map(|height| (height * 255 / 9) as u8))
+}
 
-    let (from, to) = {
-        if side == Side::LEFT {
-            (0, j)
-        } else if side == Side::RIGHT {
-            (j + 1, row.len())
-        } else if side == Side::TOP {
-            (0, i)
-        } else {
-            (i + 1, row.len())
-        }
-    };
+/// Trees between `point` and the edge on `side`, ordered left to right or top
+/// to bottom.
+fn extract_side(tokens: &Tokens, point: Point, side: Side) -> Vec<u32> {
+    let (x, y) = (point.x as usize, point.y as usize);
 
-    let trees = &row[from..to];
-    return Vec::from(trees);
+    match side {
+        Side::LEFT => tokens.row(y)[..x].to_vec(),
+        Side::RIGHT => tokens.row(y)[x + 1..].to_vec(),
+        Side::TOP => tokens.column(x).take(y).copied().collect(),
+        Side::BOTTOM => tokens.column(x).skip(y + 1).copied().collect(),
+    }
 }
 
 fn scenic_score(height: u32, trees: &[u32], side: Side) -> i32 {
@@ -123,6 +103,28 @@ pub enum Side {
     BOTTOM,
 }
 
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Tokens;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,23 +138,102 @@ mod tests {
 
     #[test]
     fn test_extract_side() {
-        let trees = Vec::from([
+        let trees = Grid::from_rows(Vec::from([
             Vec::from([2, 0, 0, 1, 1, 6]),
             Vec::from([0, 0, 1, 1, 2, 9]),
             Vec::from([3, 1, 1, 2, 1, 2]),
             Vec::from([3, 4, 3, 2, 2, 3]),
             Vec::from([1, 0, 2, 3, 3, 6]),
             Vec::from([5, 2, 3, 9, 7, 2]),
-        ]);
+        ]))
+        .unwrap();
+        let point = Point::new(2, 3);
 
-        assert_eq!(extract_side(&trees, 3, 2, Side::LEFT), Vec::from([3, 4]));
+        assert_eq!(extract_side(&trees, point, Side::LEFT), Vec::from([3, 4]));
         assert_eq!(
-            extract_side(&trees, 3, 2, Side::RIGHT),
+            extract_side(&trees, point, Side::RIGHT),
             Vec::from([2, 2, 3])
         );
-        assert_eq!(extract_side(&trees, 3, 2, Side::TOP), Vec::from([0, 1, 1]));
-        assert_eq!(extract_side(&trees, 3, 2, Side::BOTTOM), Vec::from([2, 3]));
+        assert_eq!(extract_side(&trees, point, Side::TOP), Vec::from([0, 1, 1]));
+        assert_eq!(extract_side(&trees, point, Side::BOTTOM), Vec::from([2, 3]));
     }
-}
 
-pub const CORRECT_ANSWER: i32 = 284648;
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("123\n4x6\n789\n"),
+            Err(ParseError::new(8, 2, 2, "x", "a tree height"))
+        );
+        assert_eq!(
+            parse_input("123\n456\n78\n"),
+            Err(ParseError::new(8, 3, 1, "78", "a rectangular grid"))
+        );
+        assert_eq!(
+            parse_input("123\n456\n"),
+            Err(ParseError::new(8, 1, 1, "123", "a square grid of trees"))
+        );
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 21);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 8);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day8>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            assert_eq!(parse_input(&tokens.to_string()).unwrap(), tokens);
+        }
+    }
+
+    /// Looks along the row and column of every tree.
+    #[test]
+    fn test_reference() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 12)).unwrap();
+            let rows: Vec<Vec<u32>> = tokens.rows().map(<[u32]>::to_vec).collect();
+            let size = rows.len();
+
+            let (mut visible, mut best) = (0, 0);
+            for y in 0..size {
+                for x in 0..size {
+                    let height = rows[y][x];
+                    let lines: [Vec<u32>; 4] = [
+                        (0..x).rev().map(|x| rows[y][x]).collect(),
+                        (x + 1..size).map(|x| rows[y][x]).collect(),
+                        (0..y).rev().map(|y| rows[y][x]).collect(),
+                        (y + 1..size).map(|y| rows[y][x]).collect(),
+                    ];
+                    if lines
+                        .iter()
+                        .any(|line| line.iter().all(|tree| *tree < height))
+                    {
+                        visible += 1;
+                    }
+                    let score: usize = lines
+                        .iter()
+                        .map(|line| match line.iter().position(|tree| *tree >= height) {
+                            Some(blocking) => blocking + 1,
+                            None => line.len(),
+                        })
+                        .product();
+                    best = best.max(score);
+                }
+            }
+            assert_eq!((part1(&tokens), part2(&tokens) as usize), (visible, best));
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use aoc_common::Rng;
+
+/// Motions in the puzzle input.
+pub const SIZE: usize = 2000;
+
+/// `size` motions of 1 to 20 steps each.
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+
+    (0..size)
+        .map(|_| {
+            let (direction, _) = rng.pick(&crate::DIRECTIONS);
+            format!("{direction} {}\n", rng.range(1..=20))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_input, part1, part2};
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(generate(3, 100), generate(3, 100));
+        for seed in 0..10 {
+            let tokens = parse_input(&generate(seed, 100)).unwrap();
+            assert_eq!(tokens.len(), 100);
+            // the last knot lags behind the second one
+            assert!((1..=part1(&tokens)).contains(&part2(&tokens)));
+        }
+    }
+}
@@ -1,10 +1,10 @@
-use core::panic;
-use std::fs::File;
-use std::io::prelude::*;
-use std::iter;
-use std::str::FromStr;
+pub mod generate;
 
-#[derive(Debug)]
+use std::fmt;
+
+use aoc_common::{Grid, Image, Lines, ParseError, Point, Simulation, Solution};
+
+#[derive(Debug, PartialEq)]
 pub enum Token {
     L(i32),
     D(i32),
@@ -12,225 +12,247 @@ pub enum Token {
     R(i32),
 }
 
-impl FromStr for Token {
-    type Err = ();
+type Tokens = Vec<Token>;
 
-    fn from_str(input: &str) -> Result<Token, ()> {
-        let split: Vec<&str> = input.split_whitespace().collect();
-        if split.len() != 2 {
-            panic!("Input line not as expected!")
-        }
-        let (left, right) = (split[0], split[1]);
-        match left {
-            "L" => Ok(Token::L(right.parse().unwrap())),
-            "D" => Ok(Token::D(right.parse().unwrap())),
-            "U" => Ok(Token::U(right.parse().unwrap())),
-            "R" => Ok(Token::R(right.parse().unwrap())),
-            _ => unreachable!("Pick cant be parsed!"),
-        }
-    }
+type Direction = fn(i32) -> Token;
+
+const DIRECTIONS: [(&str, Direction); 4] = [
+    ("L", Token::L),
+    ("D", Token::D),
+    ("U", Token::U),
+    ("R", Token::R),
+];
+
+pub fn parse_input(input: &str) -> Result<Tokens, ParseError> {
+    Lines::new(9, input).parse_each(|line| {
+        let direction = line.one_of(&DIRECTIONS, "L, D, U or R")?;
+        line.literal(" ")?;
+        Ok(direction(line.unsigned("a step count")?))
+    })
 }
 
-type Tokens = Vec<Token>;
+pub fn part1(tokens: &Tokens) -> i32 {
+    count_tail_visits(tokens, 2)
+}
 
-pub fn read_input() -> Result<String, String> {
-    let Ok(mut file) = File::open("input/input.txt") else {
-        return Err(String::from("File failed to open!")) };
+pub fn part2(tokens: &Tokens) -> i32 {
+    count_tail_visits(tokens, 10)
+}
 
-    let mut contents = String::with_capacity(10000);
+fn count_tail_visits(tokens: &Tokens, knots: usize) -> i32 {
+    let mut rope = Rope::new(tokens, knots);
+    while rope.step() {}
 
-    match file.read_to_string(&mut contents) {
-        Ok(_) => return Ok(contents),
-        Err(_) => return Err(String::from("File failed to be read to String!")),
-    }
+    rope.field
+        .visited
+        .iter()
+        .filter(|(_, visited)| **visited)
+        .count() as i32
 }
 
-pub fn parse_input(input: &String) -> Tokens {
-    let mut tokens = Tokens::new();
+/// Part 1 follows a rope of 2 knots, part 2 one of 10 knots.
+pub fn simulation(tokens: &Tokens, part: u8) -> Box<dyn Simulation> {
+    let knots = if part == 1 { 2 } else { 10 };
 
-    for line in input.lines() {
-        tokens.push(Token::from_str(line).unwrap());
-    }
+    Box::new(Rope::new(tokens, knots))
+}
+
+/// Positions visited by the tail in white, with `y` growing upwards.
+pub fn image(tokens: &Tokens, part: u8) -> Image {
+    let knots = if part == 1 { 2 } else { 10 };
+    let mut rope = Rope::new(tokens, knots);
+    while rope.step() {}
 
-    tokens
+    let visited = &rope.field.visited;
+    let flipped = visited.rows().rev().map(|row| row.to_vec()).collect();
+    let flipped = Grid::from_rows(flipped).unwrap();
+
+    Image::Grey(flipped.map(|visited| if *visited { 255 } else { 0 }))
 }
 
-pub fn get_answer(tokens: &Tokens) -> i32 {
-    let mut field = Field {
-        size: 2000,
-        spots: '.',
-        rope: Vec::from([
-            (1000, 1000),
-            (1000, 1000),
-            (1000, 1000),
-            (1000, 1000),
-            (1000, 1000),
-            (1000, 1000),
-            (1000, 1000),
-            (1000, 1000),
-            (1000, 1000),
-            (1000, 1000),
-        ]),
-        visited: Vec::from([(1000, 1000)]),
-    };
-
-    field.render(&tokens);
-
-    field.visited.len() as i32
+/// The rope moving one head step at a time.
+struct Rope {
+    field: Field,
+    /// Unit step and step count of each motion.
+    motions: Vec<(Point, i32)>,
+    /// The motion being made and the steps left in it.
+    next: usize,
+    left: i32,
+    /// Head steps made so far.
+    moves: u64,
 }
-struct Field {
-    size: i32,
-    spots: char,
-    rope: Vec<(i32, i32)>,
-    visited: Vec<(i32, i32)>,
+
+impl Rope {
+    fn new(tokens: &Tokens, knots: usize) -> Self {
+        Self {
+            field: Field::new(tokens, knots),
+            motions: tokens.iter().map(Token::step).collect(),
+            next: 0,
+            left: 0,
+            moves: 0,
+        }
+    }
 }
 
-fn move_tail(tail: (i32, i32), head: (i32, i32)) -> (i32, i32) {
-    let mut tail = tail;
-    let i_diff = (head.0 - tail.0).abs();
-    let j_diff = (head.1 - tail.1).abs();
+impl Simulation for Rope {
+    fn step(&mut self) -> bool {
+        while self.left == 0 {
+            let Some((_, steps)) = self.motions.get(self.next) else {
+                return false;
+            };
+            self.left = *steps;
+            self.next += 1;
+        }
+        self.left -= 1;
+        self.moves += 1;
 
-    if i_diff <= 1 && j_diff <= 1 {
-        return tail;
+        self.field.rope[0] += self.motions[self.next - 1].0;
+        self.field.move_rope();
+        true
     }
 
-    if i_diff == 0 {
-        tail.1 = if head.1 > tail.1 {
-            head.1 - 1
-        } else {
-            head.1 + 1
-        };
-        return tail;
+    fn frame(&self) -> String {
+        self.field.to_string()
     }
 
-    if j_diff == 0 {
-        tail.0 = if head.0 > tail.0 {
-            head.0 - 1
-        } else {
-            head.0 + 1
-        };
-        return tail;
+    /// Knot positions are relative to the start, with `y` growing upwards.
+    fn variables(&self) -> Vec<(&'static str, i64)> {
+        let rope = &self.field.rope;
+        let head = rope[0] - self.field.start;
+        let tail = rope[rope.len() - 1] - self.field.start;
+        vec![
+            ("move", self.moves as i64),
+            ("head_x", head.x as i64),
+            ("head_y", head.y as i64),
+            ("tail_x", tail.x as i64),
+            ("tail_y", tail.y as i64),
+            ("visited", self.field.visits as i64),
+        ]
     }
+}
 
-    if j_diff == 2 && i_diff == 2 {
-        tail.1 = if head.1 > tail.1 {
-            head.1 - 1
-        } else {
-            head.1 + 1
-        };
-        tail.0 = if head.0 > tail.0 {
-            head.0 - 1
-        } else {
-            head.0 + 1
-        };
-        return tail;
+/// A motion as written in the input, like `R 4`.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::L(steps) => write!(f, "L {steps}"),
+            Token::D(steps) => write!(f, "D {steps}"),
+            Token::U(steps) => write!(f, "U {steps}"),
+            Token::R(steps) => write!(f, "R {steps}"),
+        }
     }
+}
 
-    if j_diff == 2 {
-        // move diagonally
-        tail.0 = head.0;
-        tail.1 = if head.1 > tail.1 {
-            head.1 - 1
-        } else {
-            head.1 + 1
-        };
+impl Token {
+    /// Unit step of the head, `U` goes up towards larger `y`, and the number of
+    /// steps.
+    fn step(&self) -> (Point, i32) {
+        match *self {
+            Token::L(steps) => (Point::new(-1, 0), steps),
+            Token::D(steps) => (Point::new(0, -1), steps),
+            Token::U(steps) => (Point::new(0, 1), steps),
+            Token::R(steps) => (Point::new(1, 0), steps),
+        }
     }
+}
 
-    if i_diff == 2 {
-        // move diagonally
-        tail.0 = if head.0 > tail.0 {
-            head.0 - 1
-        } else {
-            head.0 + 1
-        };
-        tail.1 = head.1;
+struct Field {
+    visited: Grid<bool>,
+    /// Positions the tail visited so far.
+    visits: usize,
+    start: Point,
+    rope: Vec<Point>,
+}
+
+fn move_tail(tail: Point, head: Point) -> Point {
+    let diff = head - tail;
+
+    if diff.x.abs() <= 1 && diff.y.abs() <= 1 {
+        return tail;
     }
 
-    return tail;
+    tail + Point::new(diff.x.signum(), diff.y.signum())
 }
 
 impl Field {
-    fn move_rope(&mut self, prev_rope: &Vec<(i32, i32)>) {
-        for i in 1..prev_rope.len() {
-            self.rope[i] = move_tail(prev_rope[i], self.rope[i - 1]);
+    /// The field is sized to the path of the head, which the other knots never
+    /// leave.
+    fn new(tokens: &Tokens, knots: usize) -> Self {
+        let (mut head, mut min, mut max) = (Point::default(), Point::default(), Point::default());
+        for token in tokens {
+            let (step, steps) = token.step();
+            head += Point::new(step.x * steps, step.y * steps);
+            min = Point::new(min.x.min(head.x), min.y.min(head.y));
+            max = Point::new(max.x.max(head.x), max.y.max(head.y));
         }
 
-        if !self.visited.contains(&self.rope[prev_rope.len() - 1]) {
-            self.visited.push(self.rope[prev_rope.len() - 1]);
+        let size = max - min;
+        let start = Point::default() - min;
+        let mut visited = Grid::new(size.x as usize + 1, size.y as usize + 1, false);
+        visited[start] = true;
+
+        Self {
+            visited,
+            visits: 1,
+            start,
+            rope: vec![start; knots],
         }
     }
 
-    fn render(&mut self, tokens: &Tokens) {
-        let mut prev_rope;
-        for token in tokens {
-            match token {
-                Token::L(steps) => {
-                    for _ in 1..=*steps {
-                        prev_rope = self.rope.clone();
-                        self.rope[0].0 -= 1;
-                        self.move_rope(&prev_rope);
-                    }
-                }
-                Token::D(steps) => {
-                    for _ in 1..=*steps {
-                        prev_rope = self.rope.clone();
-                        self.rope[0].1 -= 1;
-                        self.move_rope(&prev_rope);
-                    }
-                }
-                Token::U(steps) => {
-                    for _ in 1..=*steps {
-                        prev_rope = self.rope.clone();
-                        self.rope[0].1 += 1;
-                        self.move_rope(&prev_rope);
-                    }
-                }
-                Token::R(steps) => {
-                    for _ in 1..=*steps {
-                        prev_rope = self.rope.clone();
-                        self.rope[0].0 += 1;
-                        self.move_rope(&prev_rope);
-                    }
-                }
-            }
+    fn move_rope(&mut self) {
+        for i in 1..self.rope.len() {
+            self.rope[i] = move_tail(self.rope[i], self.rope[i - 1]);
+        }
+
+        let tail = self.rope[self.rope.len() - 1];
+        if !self.visited[tail] {
+            self.visited[tail] = true;
+            self.visits += 1;
         }
     }
 }
 
-impl ToString for Field {
-    fn to_string(&self) -> String {
-        let mut spots: Vec<Vec<char>> = {
-            let mut tmp_spots: Vec<Vec<char>> = Vec::new();
-            for _ in 0..self.size {
-                let tmp = iter::repeat(self.spots).take(self.size as usize).collect();
-                tmp_spots.push(tmp);
-            }
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (width, height) = (self.visited.width(), self.visited.height());
+        // rows are drawn top down, so `y` is flipped
+        let flip = |point: Point| Point::new(point.x, height as i32 - 1 - point.y);
 
-            tmp_spots
-        };
-
-        for visit in &self.visited {
-            spots[visit.1 as usize][visit.0 as usize] = 's';
+        let mut spots = Grid::new(width, height, '.');
+        for (point, visited) in self.visited.iter() {
+            if *visited {
+                spots[flip(point)] = 's';
+            }
         }
 
         for i in (1..self.rope.len()).rev() {
-            spots[self.rope[i].1 as usize][self.rope[i].0 as usize] =
-                char::from_digit(i as u32, 10).unwrap();
+            spots[flip(self.rope[i])] = char::from_digit(i as u32, 10).unwrap();
         }
-        spots[self.rope[0].1 as usize][self.rope[0].0 as usize] = 'H';
+        spots[flip(self.rope[0])] = 'H';
 
-        let mut string = String::with_capacity(self.size as usize * self.size as usize);
+        write!(f, "{spots}")
+    }
+}
 
-        spots.reverse();
+pub struct Day9;
 
-        for vec in spots {
-            for ch in vec {
-                string.push(ch);
-            }
-            string.push('\n');
-        }
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Tokens;
+    type Output1 = i32;
+    type Output2 = i32;
 
-        string
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
 }
 
@@ -240,24 +262,6 @@ mod tests {
 
     #[test]
     fn test_field_moving_twn() {
-        let mut field = Field {
-            size: 5,
-            spots: '.',
-            rope: Vec::from([
-                (0, 0),
-                (0, 0),
-                (0, 0),
-                (0, 0),
-                (0, 0),
-                (0, 0),
-                (0, 0),
-                (0, 0),
-                (0, 0),
-                (0, 0),
-            ]),
-            visited: Vec::from([(0, 0)]),
-        };
-
         let tokens = Vec::from([
             Token::R(4),
             Token::U(4),
@@ -268,10 +272,101 @@ mod tests {
             Token::L(5),
             Token::R(2),
         ]);
-        field.render(&tokens);
+        let mut rope = Rope::new(&tokens, 10);
+        while rope.step() {}
 
-        assert_eq!(field.to_string(), ".....\n.....\n.1H3.\n.5...\n6....\n");
+        assert_eq!(rope.frame(), "......\n......\n.1H3..\n.5....\n6.....\n");
     }
-}
 
-pub const CORRECT_ANSWER: i32 = 2522;
+    const EXAMPLE: &str = include_str!("../input/example.txt");
+
+    const LARGER_EXAMPLE: &str = include_str!("../input/larger_example.txt");
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse_input("R 4\nX 4\n"),
+            Err(ParseError::new(9, 2, 1, "X", "L, D, U or R"))
+        );
+        assert_eq!(
+            parse_input("R 4\nU 4\nL three\n"),
+            Err(ParseError::new(9, 3, 3, "three", "a step count"))
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        use aoc_common::{DebugCommand, Debugger};
+
+        let tokens = parse_input(LARGER_EXAMPLE).unwrap();
+        let mut rope = simulation(&tokens, 2);
+        let mut debugger = Debugger::new(rope.as_mut());
+        let until = DebugCommand::parse("until head_x == -11").unwrap();
+        debugger.execute(&until).unwrap();
+        assert!(debugger.variables().contains(&("tail_x", -2)));
+        debugger.execute(&DebugCommand::Run).unwrap();
+        assert!(debugger.variables().contains(&("visited", 36)));
+    }
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse_input(EXAMPLE).unwrap()), 13);
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse_input(EXAMPLE).unwrap()), 1);
+        assert_eq!(part2(&parse_input(LARGER_EXAMPLE).unwrap()), 36);
+    }
+
+    #[test]
+    fn test_fuzz() {
+        aoc_common::fuzz::<Day9>(300, |seed| generate::generate(seed, 5));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 10)).unwrap();
+            let text: String = tokens.iter().map(|token| format!("{token}\n")).collect();
+            assert_eq!(parse_input(&text).unwrap(), tokens);
+        }
+    }
+
+    /// Follows the knots on an unbounded plane, keeping the tail positions in a
+    /// set.
+    #[test]
+    fn test_reference() {
+        use std::collections::HashSet;
+
+        let visits = |tokens: &Tokens, knots: usize| {
+            let mut rope = vec![(0i32, 0i32); knots];
+            let mut visited = HashSet::from([(0, 0)]);
+            for token in tokens {
+                let ((dx, dy), steps) = match *token {
+                    Token::L(steps) => ((-1, 0), steps),
+                    Token::D(steps) => ((0, -1), steps),
+                    Token::U(steps) => ((0, 1), steps),
+                    Token::R(steps) => ((1, 0), steps),
+                };
+                for _ in 0..steps {
+                    rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+                    for i in 1..knots {
+                        let (x, y) = (rope[i - 1].0 - rope[i].0, rope[i - 1].1 - rope[i].1);
+                        if x.abs() > 1 || y.abs() > 1 {
+                            rope[i] = (rope[i].0 + x.clamp(-1, 1), rope[i].1 + y.clamp(-1, 1));
+                        }
+                    }
+                    visited.insert(rope[knots - 1]);
+                }
+            }
+            visited.len() as i32
+        };
+
+        for seed in 0..20 {
+            let tokens = parse_input(&generate::generate(seed, 50)).unwrap();
+            let expected = (visits(&tokens, 2), visits(&tokens, 10));
+            assert_eq!((part1(&tokens), part2(&tokens)), expected);
+        }
+    }
+}